@@ -0,0 +1,207 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+#[derive(Clone, Copy, Default)]
+pub enum BitOrder {
+    #[default]
+    Msb0,
+    Lsb0,
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum Endian {
+    #[default]
+    Big,
+    Little,
+}
+
+/// arguments of the outer attribute, e.g.
+/// #[bitfield(bit_order = "lsb0", endian = "little")]
+pub struct BitfieldArgs {
+    pub bit_order: BitOrder,
+    pub endian: Endian,
+    // with `filled = false` the storage is rounded up to whole bytes instead
+    // of requiring the fields to add up to a multiple of 8 bits
+    pub filled: bool,
+    // expected layout size from `bytes = N` / `bits = N`, checked at compile time
+    pub bytes: Option<syn::LitInt>,
+    pub bits: Option<syn::LitInt>,
+    // `repr = u32` stores the bitfield in one integer instead of a byte array
+    pub repr: Option<syn::Ident>,
+    // `atomic` adds an `Atomic{Struct}` companion type; kept for the span of
+    // the width check
+    pub atomic: Option<syn::Path>,
+    // `views` adds `{Struct}Ref<'a>` / `{Struct}Mut<'a>` over borrowed bytes
+    pub views: bool,
+    endian_set: bool,
+}
+
+impl Default for BitfieldArgs {
+    fn default() -> Self {
+        BitfieldArgs {
+            bit_order: BitOrder::default(),
+            endian: Endian::default(),
+            filled: true,
+            bytes: None,
+            bits: None,
+            repr: None,
+            atomic: None,
+            views: false,
+            endian_set: false,
+        }
+    }
+}
+
+impl BitfieldArgs {
+    pub fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("bit_order") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            self.bit_order = match value.value().as_str() {
+                "msb0" => BitOrder::Msb0,
+                "lsb0" => BitOrder::Lsb0,
+                _ => {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        "expected `bit_order = \"msb0\"` or `bit_order = \"lsb0\"`",
+                    ))
+                }
+            };
+            Ok(())
+        } else if meta.path.is_ident("endian") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            self.endian_set = true;
+            self.endian = match value.value().as_str() {
+                "big" => Endian::Big,
+                "little" => Endian::Little,
+                _ => {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        "expected `endian = \"big\"` or `endian = \"little\"`",
+                    ))
+                }
+            };
+            self.check_repr(&meta)
+        } else if meta.path.is_ident("filled") {
+            let value: syn::LitBool = meta.value()?.parse()?;
+            self.filled = value.value;
+            self.check_repr(&meta)
+        } else if meta.path.is_ident("bytes") {
+            self.bytes = Some(parse_size(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("bits") {
+            self.bits = Some(parse_size(meta)?);
+            Ok(())
+        } else if meta.path.is_ident("atomic") {
+            self.atomic = Some(meta.path.clone());
+            Ok(())
+        } else if meta.path.is_ident("views") {
+            self.views = true;
+            self.check_repr(&meta)
+        } else if meta.path.is_ident("repr") {
+            let ty: syn::Ident = meta.value()?.parse()?;
+            if !["u8", "u16", "u32", "u64", "u128"].iter().any(|t| ty == t) {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    "expected one of `u8`, `u16`, `u32`, `u64` or `u128`",
+                ));
+            }
+            self.repr = Some(ty);
+            self.check_repr(&meta)
+        } else {
+            Err(meta.error("unsupported bitfield argument"))
+        }
+    }
+
+    // an integer is in native byte order and has no room for padding
+    fn check_repr(&self, meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if self.repr.is_some() && (self.endian_set || !self.filled) {
+            return Err(meta.error("`repr` cannot be combined with `endian` or `filled = false`"));
+        }
+        if self.repr.is_some() && self.views {
+            return Err(meta.error("`repr` cannot be combined with `views`"));
+        }
+        Ok(())
+    }
+
+    /// layout arguments trailing the offset and width in calls to
+    /// `bitfield::private::{read_bits, write_bits}`
+    pub fn layout(&self) -> TokenStream {
+        let bit_order = self.bit_order();
+        let endian = self.endian();
+        quote! {#bit_order, #endian}
+    }
+
+    pub fn bit_order(&self) -> TokenStream {
+        match self.bit_order {
+            BitOrder::Msb0 => quote! {::bitfield::private::BitOrder::Msb0},
+            BitOrder::Lsb0 => quote! {::bitfield::private::BitOrder::Lsb0},
+        }
+    }
+
+    /// number of bytes of storage given the total width of the fields
+    pub fn byte_size(&self, bit_width: &TokenStream) -> TokenStream {
+        if self.filled {
+            quote! {(#bit_width) / 8usize}
+        } else {
+            quote! {((#bit_width) + 7usize) / 8usize}
+        }
+    }
+
+    pub fn endian(&self) -> TokenStream {
+        // `repr` storage is viewed through its big endian bytes, which keeps
+        // the storage integer equal to the integer itself
+        if self.repr.is_some() {
+            return quote! {::bitfield::private::Endian::Big};
+        }
+        match self.endian {
+            Endian::Big => quote! {::bitfield::private::Endian::Big},
+            Endian::Little => quote! {::bitfield::private::Endian::Little},
+        }
+    }
+
+    /// type of the `data` field holding the bitfield
+    pub fn storage_ty(&self, byte_size: &TokenStream) -> TokenStream {
+        match self.repr {
+            Some(ref repr) => quote! {#repr},
+            None => quote! {[u8; #byte_size]},
+        }
+    }
+
+    pub fn storage_zero(&self, byte_size: &TokenStream) -> TokenStream {
+        match self.repr {
+            Some(_) => quote! {0},
+            None => quote! {[0; #byte_size]},
+        }
+    }
+
+    /// the storage at `place` as a `&[u8]` for `bitfield::private::read_bits`
+    pub fn bytes(&self, place: &TokenStream) -> TokenStream {
+        match self.repr {
+            Some(_) => quote! {&#place.to_be_bytes()},
+            None => quote! {&#place},
+        }
+    }
+
+    /// runs `write` with `bytes` bound to the storage at `place` as a
+    /// `&mut [u8]`, for `bitfield::private::write_bits`
+    pub fn write_bytes(&self, place: &TokenStream, write: &TokenStream) -> TokenStream {
+        match self.repr {
+            Some(ref repr) => quote! {
+                let mut storage = #place.to_be_bytes();
+                let bytes = &mut storage;
+                #write
+                #place = #repr::from_be_bytes(storage);
+            },
+            None => quote! {
+                let bytes = &mut #place;
+                #write
+            },
+        }
+    }
+}
+
+fn parse_size(meta: syn::meta::ParseNestedMeta) -> syn::Result<syn::LitInt> {
+    let value: syn::LitInt = meta.value()?.parse()?;
+    value.base10_parse::<usize>()?;
+    Ok(value)
+}
@@ -0,0 +1,295 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::ext::IdentExt;
+
+/// one field of the struct annotated with #[bitfield]
+pub struct BitfieldField<'a> {
+    // name used for the accessors: the field ident without any `r#`, or its
+    // position for tuple structs
+    pub name: String,
+    pub span: Span,
+    // specifier of the field, or of each element for array fields
+    pub ty: &'a syn::Type,
+    // number of elements for array fields like `lanes: [B4; 8]`
+    pub len: Option<&'a syn::Expr>,
+    // width given by an optional #[bits = N], checked against the specifier
+    pub tagged_width: Option<&'a syn::LitInt>,
+    // explicit offsets given by #[bits(lo..=hi)], also checked against the
+    // specifier
+    pub position: Option<BitPosition>,
+    // whether public getters / setters are generated, turned off by #[skip]
+    // and for padding fields whose name starts with an underscore
+    pub getters: bool,
+    pub setters: bool,
+}
+
+impl<'a> BitfieldField<'a> {
+    fn new(index: usize, field: &'a syn::Field) -> syn::Result<Self> {
+        let (name, span) = match field.ident {
+            Some(ref ident) => (ident.unraw().to_string(), ident.span()),
+            None => (index.to_string(), Span::call_site()),
+        };
+        let (ty, len) = match field.ty {
+            syn::Type::Array(ref array) => (&*array.elem, Some(&array.len)),
+            ref ty => (ty, None),
+        };
+        let padding = is_padding(&name);
+        let mut tagged_width = None;
+        let mut position = None;
+        let mut skip = None;
+        for attr in &field.attrs {
+            if attr.path().is_ident("doc") {
+                continue;
+            }
+            if attr.path().is_ident("skip") {
+                if skip.is_some() {
+                    return Err(syn::Error::new_spanned(attr, "duplicate #[skip] attribute"));
+                }
+                skip = Some(parse_skip(attr)?);
+                continue;
+            }
+            if !attr.path().is_ident("bits") {
+                return Err(syn::Error::new_spanned(
+                    attr.path(),
+                    "unrecognized bitfield attribute, expected #[bits = N], #[bits(lo..=hi)] or #[skip]",
+                ));
+            }
+            if tagged_width.is_some() || position.is_some() {
+                return Err(syn::Error::new_spanned(attr, "duplicate #[bits] attribute"));
+            }
+            if let syn::Meta::List(_) = attr.meta {
+                position = Some(BitPosition::parse(attr)?);
+                continue;
+            }
+            let value = &attr.meta.require_name_value()?.value;
+            match value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Int(lit),
+                    ..
+                }) => {
+                    lit.base10_parse::<usize>()?;
+                    tagged_width = Some(lit);
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected an integer literal in #[bits = N]",
+                    ))
+                }
+            }
+        }
+        let (skip_getters, skip_setters) = skip.unwrap_or((padding, padding));
+        Ok(BitfieldField {
+            name,
+            span,
+            ty,
+            len,
+            tagged_width,
+            position,
+            getters: !skip_getters,
+            setters: !skip_setters,
+        })
+    }
+
+    pub fn is_padding(&self) -> bool {
+        is_padding(&self.name)
+    }
+
+    /// number of bits taken up by the field as a const expression
+    pub fn bit_width(&self) -> TokenStream {
+        let ty = self.ty;
+        match self.len {
+            Some(len) => quote! {(<#ty as Specifier>::BITS * (#len))},
+            None => quote! {<#ty as Specifier>::BITS},
+        }
+    }
+
+    /// array fields are accessed one element at a time, the index being the
+    /// first argument of every accessor: the index parameter and argument,
+    /// the offset of the element given the field's `offset`, and the bounds
+    /// check of the index
+    pub fn indexing(
+        &self,
+        offset: &TokenStream,
+    ) -> (TokenStream, TokenStream, TokenStream, TokenStream) {
+        let ty = self.ty;
+        match self.len {
+            Some(len) => {
+                let msg = format!("index out of bounds for field `{}`", self.name);
+                (
+                    quote! {index: usize,},
+                    quote! {index,},
+                    quote! {(#offset) + index * <#ty as Specifier>::BITS},
+                    quote! {assert!(index < (#len), #msg);},
+                )
+            }
+            None => (quote! {}, quote! {}, offset.clone(), quote! {}),
+        }
+    }
+
+    /// debug assertion of the setters that the bit repr `repr` of a value
+    /// fits into the field
+    pub fn range_assertion(&self) -> TokenStream {
+        let ty = self.ty;
+        let name = &self.name;
+        quote! {
+            const OVERFLOW: ::bitfield::private::OverflowMessage =
+                ::bitfield::private::OverflowMessage::new(#name, <#ty as Specifier>::BITS);
+            debug_assert!(
+                ::bitfield::private::fits(repr as u128, <#ty as Specifier>::BITS),
+                "{}",
+                OVERFLOW.as_str(),
+            );
+        }
+    }
+}
+
+/// bit offsets `lo..=hi` pinned by #[bits(lo..=hi)], counted in the bit order
+/// of the bitfield like all other offsets
+pub struct BitPosition {
+    pub lo: usize,
+    pub hi: usize,
+    pub range: syn::ExprRange,
+}
+
+impl BitPosition {
+    fn parse(attr: &syn::Attribute) -> syn::Result<Self> {
+        let range: syn::ExprRange = attr.parse_args()?;
+        let bound = |expr: &Option<Box<syn::Expr>>| match expr.as_deref() {
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(lit),
+                ..
+            })) => lit.base10_parse::<usize>().ok(),
+            _ => None,
+        };
+        let (lo, hi) = match (bound(&range.start), &range.limits, bound(&range.end)) {
+            (Some(lo), syn::RangeLimits::Closed(_), Some(hi)) if lo <= hi => (lo, hi),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    range,
+                    "expected an inclusive range of integer literals in #[bits(lo..=hi)]",
+                ))
+            }
+        };
+        Ok(BitPosition { lo, hi, range })
+    }
+
+    pub fn width(&self) -> usize {
+        self.hi - self.lo + 1
+    }
+}
+
+// once one field has an explicit position all of them need one, and together
+// they have to cover the bits from 0 exactly once; reserved bits are spelled
+// as padding fields
+fn check_positions(fields: &[BitfieldField], syn_fields: &[&syn::Field]) -> syn::Result<()> {
+    if fields.iter().all(|f| f.position.is_none()) {
+        return Ok(());
+    }
+    if let Some(i) = fields.iter().position(|f| f.position.is_none()) {
+        return Err(syn::Error::new_spanned(
+            syn_fields[i],
+            "missing #[bits(lo..=hi)], every field needs one once any field has it",
+        ));
+    }
+    let mut positioned: Vec<_> = fields
+        .iter()
+        .filter_map(|f| Some((f.position.as_ref()?, &f.name)))
+        .collect();
+    positioned.sort_by_key(|(p, _)| p.lo);
+    let mut end = 0;
+    let mut prev: Option<(&BitPosition, &String)> = None;
+    for &(p, name) in &positioned {
+        if p.lo < end {
+            let (q, prev_name) = prev.unwrap();
+            return Err(syn::Error::new_spanned(
+                &p.range,
+                format!(
+                    "bits {}..={} overlap field `{}` at bits {}..={}",
+                    p.lo, p.hi, prev_name, q.lo, q.hi
+                ),
+            ));
+        }
+        if p.lo > end {
+            return Err(syn::Error::new_spanned(
+                &p.range,
+                format!(
+                    "bits {}..={} are not covered by any field, mark them as reserved with a padding field like `_reserved: B{}`",
+                    end,
+                    p.lo - 1,
+                    p.lo - end
+                ),
+            ));
+        }
+        end = p.hi + 1;
+        prev = Some((p, name));
+    }
+    Ok(())
+}
+
+// a bare `_` is rejected by the parser before we ever see it, so padding is
+// spelled like an unused binding: `_reserved: B3`
+fn is_padding(name: &str) -> bool {
+    name.starts_with('_')
+}
+
+// #[skip] skips both getters and setters, #[skip(getters)] and
+// #[skip(setters)] only one of them
+fn parse_skip(attr: &syn::Attribute) -> syn::Result<(bool, bool)> {
+    if let syn::Meta::Path(_) = attr.meta {
+        return Ok((true, true));
+    }
+    let (mut getters, mut setters) = (false, false);
+    attr.parse_nested_meta(|meta| {
+        let flag = if meta.path.is_ident("getters") {
+            &mut getters
+        } else if meta.path.is_ident("setters") {
+            &mut setters
+        } else {
+            return Err(meta.error("expected `getters` or `setters`"));
+        };
+        if *flag {
+            return Err(meta.error("duplicate #[skip] argument"));
+        }
+        *flag = true;
+        Ok(())
+    })?;
+    Ok((getters, setters))
+}
+
+pub fn bitfield_fields(ts: &syn::ItemStruct) -> syn::Result<Vec<BitfieldField<'_>>> {
+    if !ts.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &ts.generics,
+            "bitfield structs cannot have generic parameters",
+        ));
+    }
+    if let Some(ref where_clause) = ts.generics.where_clause {
+        return Err(syn::Error::new_spanned(
+            where_clause,
+            "bitfield structs cannot have a where clause",
+        ));
+    }
+    match ts.fields {
+        syn::Fields::Named(syn::FieldsNamed {
+            named: ref fields, ..
+        })
+        | syn::Fields::Unnamed(syn::FieldsUnnamed {
+            unnamed: ref fields,
+            ..
+        }) => {
+            let syn_fields: Vec<_> = fields.iter().collect();
+            let fields = syn_fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| BitfieldField::new(index, field))
+                .collect::<syn::Result<Vec<_>>>()?;
+            check_positions(&fields, &syn_fields)?;
+            Ok(fields)
+        }
+        syn::Fields::Unit => Err(syn::Error::new_spanned(
+            &ts.ident,
+            "bitfield structs need at least one field",
+        )),
+    }
+}
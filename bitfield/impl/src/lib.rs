@@ -1,199 +1,1005 @@
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
 use syn::visit_mut::VisitMut;
 use syn::{parse_macro_input, parse_quote};
 
+use args::BitfieldArgs;
+use field::{bitfield_fields, BitfieldField};
+
+mod args;
+mod field;
 mod specifier;
 
 static WIDTH_PTYPE: [usize; 5] = [8, 16, 32, 64, 128];
-struct BitfieldVisit;
+struct BitfieldVisit {
+    storage_ty: proc_macro2::TokenStream,
+}
 
-fn build_accessors(ts: &syn::ItemStruct) -> proc_macro2::TokenStream {
-    let methods: Vec<_> = if let syn::Fields::Named(syn::FieldsNamed {
-        named: ref fields, ..
-    }) = ts.fields
-    {
-        fields
-            .iter()
-            .scan(quote! {0}, |offset, f| {
-                // get this done in the easiest way, bit level ops
-                let ty = &f.ty;
-                let ident = f.ident.as_ref().unwrap();
-                let prev_offset = offset.clone();
-                *offset = quote! {#offset + <#ty as Specifier>::BITS};
-                let bit_index_range = quote! {(#prev_offset)..(#offset)};
-                let getter_method = {
-                    let ident = format_ident!("get_{}", ident);
-                    quote! {
-                        pub fn #ident(&self) -> <#ty as Specifier>::Target{
-                            let mut val = 0u64;
-                            for i in #bit_index_range {
-                                val <<= 1;
-                                val |= self.fetch_bit(i);
-                            }
-                            let repr = val as <#ty as Specifier>::Container;
-                            <#ty as Specifier>::from_bit_repr(repr)
-                        }
+// the type accessors are generated for: the bitfield itself, or one of the
+// views borrowing its bytes, which only get the accessors that fit the borrow
+#[derive(Clone, Copy, PartialEq)]
+enum Accessed {
+    Owned,
+    Ref,
+    Mut,
+}
+
+fn build_accessors(
+    fields: &[BitfieldField],
+    offsets: &[proc_macro2::TokenStream],
+    args: &BitfieldArgs,
+    accessed: Accessed,
+) -> proc_macro2::TokenStream {
+    let layout = args.layout();
+    // views hold a `&[u8; N]` / `&mut [u8; N]` in `data`
+    let place = match accessed {
+        Accessed::Owned => quote! {self.data},
+        Accessed::Ref | Accessed::Mut => quote! {*self.data},
+    };
+    let data = args.bytes(&place);
+    let methods: Vec<_> = fields
+        .iter()
+        .zip(offsets)
+        .map(|(f, prev_offset)| {
+            // shift-and-mask over whole words, see bitfield::private
+            let ty = f.ty;
+            let name = &f.name;
+            let span = f.span;
+
+            let (index_param, index_arg, field_offset, bound_check) = f.indexing(prev_offset);
+
+            let read_repr = quote! {
+                #bound_check
+                let repr = ::bitfield::private::read_bits(
+                    #data,
+                    #field_offset,
+                    <#ty as Specifier>::BITS,
+                    #layout,
+                ) as <#ty as Specifier>::Container;
+            };
+            let getter_method = {
+                let ident = format_ident!("get_{}", name, span = span);
+                quote! {
+                    pub const fn #ident(&self, #index_param) -> <#ty as Specifier>::Target {
+                        #read_repr
+                        <<#ty as Specifier>::Codec>::from_bit_repr(repr)
                     }
-                };
-                let setter_method = {
-                    let ident = format_ident!("set_{}", ident);
-                    quote! {
-                        pub fn #ident(&mut self, val: <#ty as Specifier>::Target) {
-                            let mut val = <#ty as Specifier>::from_target(val);
-                            for i in (#bit_index_range).rev() {
-                                self.set_bit(i, (val & 0x1) as u8);
-                                val >>= 1;
-                            }
+                }
+            };
+            let fallible_getter_method = {
+                let ident = format_ident!("get_{}_or_err", name, span = span);
+                quote! {
+                    pub const fn #ident(
+                        &self,
+                        #index_param
+                    ) -> ::core::result::Result<
+                        <#ty as Specifier>::Target,
+                        ::bitfield::InvalidBitPattern<<#ty as Specifier>::Container>,
+                    > {
+                        #read_repr
+                        <<#ty as Specifier>::Codec>::try_from_bit_repr(repr)
+                    }
+                }
+            };
+            let write_repr = args.write_bytes(
+                &place,
+                &quote! {
+                    ::bitfield::private::write_bits(
+                        bytes,
+                        #field_offset,
+                        <#ty as Specifier>::BITS,
+                        #layout,
+                        repr as u128,
+                    );
+                },
+            );
+            let setter_method = {
+                let range_assertion = f.range_assertion();
+                let ident = format_ident!("set_{}", name, span = span);
+                quote! {
+                    pub const fn #ident(&mut self, #index_param val: <#ty as Specifier>::Target) {
+                        #bound_check
+                        let repr = <<#ty as Specifier>::Codec>::from_target(val);
+                        #range_assertion
+                        #write_repr
+                    }
+                }
+            };
+            let checked_setter_method = {
+                let ident = format_ident!("set_{}_checked", name, span = span);
+                quote! {
+                    pub const fn #ident(
+                        &mut self,
+                        #index_param
+                        val: <#ty as Specifier>::Target,
+                    ) -> ::core::result::Result<(), ::bitfield::OutOfBounds> {
+                        #bound_check
+                        let repr = <<#ty as Specifier>::Codec>::from_target(val);
+                        if !::bitfield::private::fits(repr as u128, <#ty as Specifier>::BITS) {
+                            return ::core::result::Result::Err(::bitfield::OutOfBounds {
+                                bits: <#ty as Specifier>::BITS,
+                            });
                         }
+                        #write_repr
+                        ::core::result::Result::Ok(())
                     }
-                };
+                }
+            };
 
-                Some(quote! {#getter_method #setter_method})
-            })
-            .collect()
-    } else {
-        unreachable!()
-    };
+            let with_method = {
+                let setter = format_ident!("set_{}", name, span = span);
+                let ident = format_ident!("with_{}", name, span = span);
+                quote! {
+                    pub const fn #ident(mut self, #index_param val: <#ty as Specifier>::Target) -> Self {
+                        self.#setter(#index_arg val);
+                        self
+                    }
+                }
+            };
+
+            let getters = f.getters.then(|| {
+                quote! {
+                    #getter_method
+                    #fallible_getter_method
+                }
+            });
+            let with_method = (accessed == Accessed::Owned).then_some(with_method);
+            let setters = (f.setters && accessed != Accessed::Ref).then(|| {
+                quote! {
+                    #setter_method
+                    #checked_setter_method
+                    #with_method
+                }
+            });
+            quote! {
+                #getters
+                #setters
+            }
+        })
+        .collect();
 
     quote! {#(#methods)*}
 }
 
-impl VisitMut for BitfieldVisit {
-    fn visit_item_struct_mut(&mut self, node: &mut syn::ItemStruct) {
-        // const evaluate bitsize of fields and replace them
-        let array_size = if let syn::Fields::Named(syn::FieldsNamed {
-            named: ref fields, ..
-        }) = node.fields
-        {
-            let size = fields.iter().map(|f| {
-                let ty = &f.ty;
-                quote! {<#ty as Specifier>::BITS}
-            });
-            quote! {(#(#size)+*) / 8usize}
-        } else {
-            unreachable!()
+fn build_layout(
+    fields: &[BitfieldField],
+    offsets: &[proc_macro2::TokenStream],
+    args: &BitfieldArgs,
+) -> proc_macro2::TokenStream {
+    let layout = args.layout();
+    let pushes: Vec<_> = fields
+        .iter()
+        .zip(offsets)
+        .map(|(f, prev_offset)| {
+            let ty = f.ty;
+
+            // padding is drawn as a blank box
+            let name = if f.is_padding() { "" } else { &f.name };
+            let readable = f.getters;
+            let (index, field_offset) = match f.len {
+                Some(_) => (
+                    quote! {::core::option::Option::Some(index)},
+                    quote! {(#prev_offset) + index * <#ty as Specifier>::BITS},
+                ),
+                None => (quote! {::core::option::Option::None}, prev_offset.clone()),
+            };
+            let push = quote! {
+                fields.push(::bitfield::private::LayoutField {
+                    name: #name,
+                    index: #index,
+                    offset: #field_offset,
+                    width: <#ty as Specifier>::BITS,
+                    readable: #readable,
+                });
+            };
+            match f.len {
+                Some(len) => quote! {
+                    for index in 0..(#len) {
+                        #push
+                    }
+                },
+                None => push,
+            }
+        })
+        .collect();
+
+    let data = args.bytes(&quote! {self.data});
+    quote! {
+        pub fn fmt_layout(&self, w: &mut impl ::core::fmt::Write) -> ::core::fmt::Result {
+            let mut fields = ::std::vec::Vec::new();
+            #(#pushes)*
+            ::bitfield::private::fmt_layout(w, #data, &fields, #layout)
+        }
+
+        pub fn layout(&self) -> ::std::string::String {
+            let mut layout = ::std::string::String::new();
+            self.fmt_layout(&mut layout)
+                .expect("a String is always writable");
+            layout
+        }
+    }
+}
+
+fn build_conversions(
+    struct_name: &syn::Ident,
+    bit_width: &proc_macro2::TokenStream,
+    args: &BitfieldArgs,
+) -> proc_macro2::TokenStream {
+    if let Some(ref repr) = args.repr {
+        return quote! {
+            impl #struct_name {
+                pub const fn from_bits(bits: #repr) -> Self {
+                    Self { data: bits }
+                }
+
+                pub const fn into_bits(self) -> #repr {
+                    self.data
+                }
+            }
+
+            impl ::core::convert::From<#repr> for #struct_name {
+                fn from(bits: #repr) -> Self {
+                    Self::from_bits(bits)
+                }
+            }
+
+            impl ::core::convert::From<#struct_name> for #repr {
+                fn from(bitfield: #struct_name) -> Self {
+                    bitfield.into_bits()
+                }
+            }
+
+            unsafe impl ::bitfield::private::ReprBits for #struct_name {
+                type Bits = #repr;
+
+                fn from_bits(bits: #repr) -> Self {
+                    Self::from_bits(bits)
+                }
+
+                fn into_bits(self) -> #repr {
+                    self.into_bits()
+                }
+            }
         };
+    }
+
+    let byte_size = args.byte_size(bit_width);
+    let layout = args.layout();
+    quote! {
+        impl #struct_name {
+            pub const fn from_bytes(bytes: [u8; #byte_size]) -> Self {
+                let mut data = bytes;
+                // bits past the last field, only there with `filled = false`,
+                // always read as zero
+                ::bitfield::private::write_bits(
+                    &mut data,
+                    #bit_width,
+                    (#byte_size) * 8 - (#bit_width),
+                    #layout,
+                    0,
+                );
+                Self { data }
+            }
+
+            pub const fn into_bytes(self) -> [u8; #byte_size] {
+                self.data
+            }
+
+            pub const fn as_bytes(&self) -> &[u8; #byte_size] {
+                &self.data
+            }
 
-        if let syn::Fields::Named(ref mut inner) = node.fields {
-            *inner = parse_quote! {
-                {
-                  data: [u8; #array_size],
+            pub const fn try_from_slice(bytes: &[u8]) -> ::core::result::Result<Self, ::bitfield::LengthMismatch> {
+                match ::bitfield::private::copy_exact::<{ #byte_size }>(bytes) {
+                    ::core::option::Option::Some(data) => ::core::result::Result::Ok(Self::from_bytes(data)),
+                    ::core::option::Option::None => ::core::result::Result::Err(::bitfield::LengthMismatch {
+                        expected: #byte_size,
+                        actual: bytes.len(),
+                    }),
                 }
-            };
+            }
+        }
+
+        impl ::core::convert::From<[u8; #byte_size]> for #struct_name {
+            fn from(bytes: [u8; #byte_size]) -> Self {
+                Self::from_bytes(bytes)
+            }
+        }
+
+        impl ::core::convert::From<#struct_name> for [u8; #byte_size] {
+            fn from(bitfield: #struct_name) -> Self {
+                bitfield.into_bytes()
+            }
+        }
+
+        impl ::core::convert::TryFrom<&[u8]> for #struct_name {
+            type Error = ::bitfield::LengthMismatch;
+
+            fn try_from(bytes: &[u8]) -> ::core::result::Result<Self, Self::Error> {
+                Self::try_from_slice(bytes)
+            }
+        }
+    }
+}
+
+// the `Codec` trait impl of a codec, forwarding to its inherent const fns;
+// `krate` is the path of the bitfield crate as seen from the expansion
+fn codec_trait_impl(
+    krate: &proc_macro2::TokenStream,
+    codec: &proc_macro2::TokenStream,
+    container: &proc_macro2::TokenStream,
+    target: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        impl #krate::Codec for #codec {
+            type Container = #container;
+            type Target = #target;
+
+            fn from_bit_repr(repr: #container) -> #target {
+                Self::from_bit_repr(repr)
+            }
+
+            fn try_from_bit_repr(
+                repr: #container,
+            ) -> ::core::result::Result<#target, #krate::InvalidBitPattern<#container>> {
+                Self::try_from_bit_repr(repr)
+            }
+
+            fn from_target(target: #target) -> #container {
+                Self::from_target(target)
+            }
+        }
+    }
+}
+
+// a bitfield struct is itself a specifier so that it can be nested in other
+// bitfields; its bit repr is the fields read as one integer, which keeps the
+// nested fields in the same order whatever the bit order of either side
+fn build_specifier_impl(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    bit_width: &proc_macro2::TokenStream,
+    args: &BitfieldArgs,
+) -> proc_macro2::TokenStream {
+    let codec_ident = format_ident!("{}Codec", struct_name);
+    let layout = args.layout();
+    let whole = quote! {0, #bit_width, #layout};
+    let write = args.write_bytes(
+        &quote! {target.data},
+        &quote! {::bitfield::private::write_bits(bytes, #whole, repr as u128);},
+    );
+    let data = args.bytes(&quote! {target.data});
+    let codec_trait = codec_trait_impl(
+        &quote! {::bitfield},
+        &quote! {#codec_ident},
+        &quote! {<#struct_name as Specifier>::Container},
+        &quote! {#struct_name},
+    );
+    quote! {
+        const _: () = {
+            #vis enum #codec_ident {}
+
+            #codec_trait
+
+            impl #codec_ident {
+                pub const fn from_bit_repr(repr: <#struct_name as Specifier>::Container) -> #struct_name {
+                    let mut target = #struct_name::new();
+                    #write
+                    target
+                }
+
+                pub const fn try_from_bit_repr(
+                    repr: <#struct_name as Specifier>::Container,
+                ) -> ::core::result::Result<
+                    #struct_name,
+                    ::bitfield::InvalidBitPattern<<#struct_name as Specifier>::Container>,
+                > {
+                    ::core::result::Result::Ok(Self::from_bit_repr(repr))
+                }
+
+                pub const fn from_target(target: #struct_name) -> <#struct_name as Specifier>::Container {
+                    ::bitfield::private::read_bits(#data, #whole) as <#struct_name as Specifier>::Container
+                }
+            }
+
+            impl Specifier for #struct_name {
+                const BITS: usize = #bit_width;
+                // clamped so that the impl is well-formed for any size; a
+                // parent bitfield rejects fields wider than 128 bits anyway
+                type Container = <::bitfield::private::Width<{
+                    if (#bit_width) > 128 { 128 } else { #bit_width }
+                }> as ::bitfield::private::Fit>::Uint;
+                type Target = #struct_name;
+                type Codec = #codec_ident;
+            }
+        };
+    }
+}
+
+// with `atomic`, a companion type holding the fields read as one integer, as
+// for nesting, in the matching atomic integer. Single fields are updated with
+// a compare-exchange loop so that concurrent updates of other fields are kept
+fn build_atomic(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    fields: &[BitfieldField],
+    offsets: &[proc_macro2::TokenStream],
+    bit_width: &proc_macro2::TokenStream,
+    args: &BitfieldArgs,
+) -> proc_macro2::TokenStream {
+    let atomic_name = format_ident!("Atomic{}", struct_name);
+    let container = quote! {<#struct_name as Specifier>::Container};
+    // the container, clamped so that a bitfield wider than 64 bits only trips
+    // the width check in `sanity_check`
+    let int = quote! {
+        <::bitfield::private::Width<{
+            if (#bit_width) > 64 { 64 } else { #bit_width }
+        }> as ::bitfield::private::Fit>::Uint
+    };
+    let codec = quote! {<#struct_name as Specifier>::Codec};
+    let ordering = quote! {::core::sync::atomic::Ordering};
+    let order = args.bit_order();
+
+    let methods = fields.iter().zip(offsets).map(|(f, prev_offset)| {
+        let ty = f.ty;
+        let name = &f.name;
+        let span = f.span;
+        let (index_param, index_arg, field_offset, bound_check) = f.indexing(prev_offset);
+        let int_bits = quote! {#bit_width, #field_offset, <#ty as Specifier>::BITS, #order};
+        let extract = format_ident!("extract_{}", name);
+        let insert = format_ident!("insert_{}", name);
+        let insert_repr = format_ident!("insert_{}_repr", name);
+        let extract_fn = (f.getters || f.setters).then(|| {
+            quote! {
+                fn #extract(#index_param int: #int) -> <#ty as Specifier>::Target {
+                    let repr = ::bitfield::private::read_int_bits(int as u128, #int_bits)
+                        as <#ty as Specifier>::Container;
+                    <<#ty as Specifier>::Codec>::from_bit_repr(repr)
+                }
+            }
+        });
+
+        let load_method = f.getters.then(|| {
+            let ident = format_ident!("load_{}", name, span = span);
+            quote! {
+                pub fn #ident(&self, #index_param order: #ordering) -> <#ty as Specifier>::Target {
+                    #bound_check
+                    Self::#extract(#index_arg self.data.load(order))
+                }
+            }
+        });
+        let update_methods = f.setters.then(|| {
+            let range_assertion = f.range_assertion();
+            let store = format_ident!("store_{}", name, span = span);
+            let fetch_update = format_ident!("fetch_update_{}", name, span = span);
+            quote! {
+                fn #insert(
+                    #index_param
+                    int: #int,
+                    val: <#ty as Specifier>::Target,
+                ) -> #int {
+                    let repr = <<#ty as Specifier>::Codec>::from_target(val);
+                    Self::#insert_repr(#index_arg int, repr)
+                }
+
+                fn #insert_repr(
+                    #index_param
+                    int: #int,
+                    repr: <#ty as Specifier>::Container,
+                ) -> #int {
+                    #range_assertion
+                    ::bitfield::private::write_int_bits(int as u128, #int_bits, repr as u128) as #int
+                }
+
+                pub fn #store(&self, #index_param val: <#ty as Specifier>::Target, order: #ordering) {
+                    #bound_check
+                    // convert once, the closure may run several times
+                    let repr = <<#ty as Specifier>::Codec>::from_target(val);
+                    let _ = self.data.fetch_update(
+                        order,
+                        ::bitfield::private::failure_ordering(order),
+                        |int| ::core::option::Option::Some(Self::#insert_repr(#index_arg int, repr)),
+                    );
+                }
+
+                pub fn #fetch_update(
+                    &self,
+                    #index_param
+                    set_order: #ordering,
+                    fetch_order: #ordering,
+                    mut f: impl FnMut(<#ty as Specifier>::Target) -> ::core::option::Option<<#ty as Specifier>::Target>,
+                ) -> ::core::result::Result<<#ty as Specifier>::Target, <#ty as Specifier>::Target> {
+                    #bound_check
+                    self.data
+                        .fetch_update(set_order, fetch_order, |int| {
+                            f(Self::#extract(#index_arg int)).map(|val| Self::#insert(#index_arg int, val))
+                        })
+                        .map(|int| Self::#extract(#index_arg int))
+                        .map_err(|int| Self::#extract(#index_arg int))
+                }
+            }
+        });
+        quote! {
+            #extract_fn
+            #load_method
+            #update_methods
+        }
+    });
+
+    quote! {
+        #[repr(transparent)]
+        #vis struct #atomic_name {
+            data: <#int as ::bitfield::private::AtomicInt>::Atomic,
+        }
+
+        impl #atomic_name {
+            pub const fn new(value: #struct_name) -> Self {
+                Self {
+                    data: <#int as ::bitfield::private::AtomicInt>::Atomic::new(
+                        #codec::from_target(value) as #int,
+                    ),
+                }
+            }
+
+            pub fn into_inner(self) -> #struct_name {
+                #codec::from_bit_repr(self.data.into_inner() as #container)
+            }
+
+            pub fn load(&self, order: #ordering) -> #struct_name {
+                #codec::from_bit_repr(self.data.load(order) as #container)
+            }
+
+            pub fn store(&self, value: #struct_name, order: #ordering) {
+                self.data.store(#codec::from_target(value) as #int, order)
+            }
+
+            #(#methods)*
+        }
+    }
+}
+
+// with `views`, `{Struct}Ref<'a>` and `{Struct}Mut<'a>` overlay the layout on
+// borrowed bytes, e.g. inside a larger buffer, without copying them out
+fn build_views(
+    struct_name: &syn::Ident,
+    vis: &syn::Visibility,
+    fields: &[BitfieldField],
+    offsets: &[proc_macro2::TokenStream],
+    byte_size: &proc_macro2::TokenStream,
+    args: &BitfieldArgs,
+) -> proc_macro2::TokenStream {
+    let ref_name = format_ident!("{}Ref", struct_name);
+    let mut_name = format_ident!("{}Mut", struct_name);
+    let ref_accessors = build_accessors(fields, offsets, args, Accessed::Ref);
+    let mut_accessors = build_accessors(fields, offsets, args, Accessed::Mut);
+
+    quote! {
+        #[derive(Clone, Copy)]
+        #vis struct #ref_name<'a> {
+            data: &'a [u8; #byte_size],
+        }
+
+        impl<'a> #ref_name<'a> {
+            pub const fn new(bytes: &'a [u8]) -> ::core::result::Result<Self, ::bitfield::LengthMismatch> {
+                match ::bitfield::private::ref_exact::<{ #byte_size }>(bytes) {
+                    ::core::option::Option::Some(data) => ::core::result::Result::Ok(Self { data }),
+                    ::core::option::Option::None => ::core::result::Result::Err(::bitfield::LengthMismatch {
+                        expected: #byte_size,
+                        actual: bytes.len(),
+                    }),
+                }
+            }
+
+            pub const fn as_bytes(&self) -> &'a [u8; #byte_size] {
+                self.data
+            }
+
+            pub const fn to_bitfield(&self) -> #struct_name {
+                #struct_name::from_bytes(*self.data)
+            }
+
+            #ref_accessors
+        }
+
+        #vis struct #mut_name<'a> {
+            data: &'a mut [u8; #byte_size],
         }
+
+        impl<'a> #mut_name<'a> {
+            pub const fn new(bytes: &'a mut [u8]) -> ::core::result::Result<Self, ::bitfield::LengthMismatch> {
+                let actual = bytes.len();
+                match ::bitfield::private::mut_exact::<{ #byte_size }>(bytes) {
+                    ::core::option::Option::Some(data) => ::core::result::Result::Ok(Self { data }),
+                    ::core::option::Option::None => ::core::result::Result::Err(::bitfield::LengthMismatch {
+                        expected: #byte_size,
+                        actual,
+                    }),
+                }
+            }
+
+            pub const fn as_bytes(&self) -> &[u8; #byte_size] {
+                self.data
+            }
+
+            pub const fn as_view(&self) -> #ref_name<'_> {
+                #ref_name { data: self.data }
+            }
+
+            pub const fn to_bitfield(&self) -> #struct_name {
+                #struct_name::from_bytes(*self.data)
+            }
+
+            pub const fn write(&mut self, bitfield: #struct_name) {
+                *self.data = bitfield.into_bytes();
+            }
+
+            #mut_accessors
+        }
+    }
+}
+
+// a derived Debug would only show the `data` array, so #[derive(Debug)] is
+// taken off the struct and replaced by an impl listing the field values;
+// PartialEq, Eq and Hash are left to compare the packed bytes. Only derives
+// below #[bitfield] reach it, one above is applied to the original fields.
+fn take_derive_debug(attrs: &mut Vec<syn::Attribute>) -> syn::Result<bool> {
+    let mut found = false;
+    let mut kept = Vec::with_capacity(attrs.len());
+    for attr in attrs.drain(..) {
+        if !attr.path().is_ident("derive") {
+            kept.push(attr);
+            continue;
+        }
+        let paths =
+            attr.parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)?;
+        let len = paths.len();
+        let paths: Punctuated<_, syn::Token![,]> = paths
+            .into_iter()
+            .filter(|path| path.segments.last().is_some_and(|seg| seg.ident != "Debug"))
+            .collect();
+        found |= paths.len() != len;
+        if !paths.is_empty() {
+            kept.push(parse_quote! {#[derive(#paths)]});
+        }
+    }
+    *attrs = kept;
+    Ok(found)
+}
+
+fn build_debug_impl(
+    struct_name: &syn::Ident,
+    fields: &[BitfieldField],
+    tuple: bool,
+) -> proc_macro2::TokenStream {
+    // only fields with getters are shown, the others are elided with `..`
+    let shown = fields.iter().filter(|f| f.getters).map(|f| {
+        let getter = format_ident!("get_{}_or_err", f.name);
+        let value = match f.len {
+            Some(len) => quote! {
+                &{
+                    let values: [_; #len] = ::core::array::from_fn(|index| {
+                        ::bitfield::private::DebugField(self.#getter(index))
+                    });
+                    values
+                }
+            },
+            None => quote! {&::bitfield::private::DebugField(self.#getter())},
+        };
+        if tuple {
+            quote! {.field(#value)}
+        } else {
+            let name = &f.name;
+            quote! {.field(#name, #value)}
+        }
+    });
+    let name = struct_name.to_string();
+    let start = if tuple {
+        quote! {debug_tuple}
+    } else {
+        quote! {debug_struct}
+    };
+    let finish = if fields.iter().all(|f| f.getters) {
+        quote! {finish}
+    } else {
+        quote! {finish_non_exhaustive}
+    };
+    quote! {
+        impl ::core::fmt::Debug for #struct_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.#start(#name)#(#shown)*.#finish()
+            }
+        }
+    }
+}
+
+impl VisitMut for BitfieldVisit {
+    fn visit_item_struct_mut(&mut self, node: &mut syn::ItemStruct) {
+        // const evaluate bitsize of fields and replace them
+        let storage_ty = &self.storage_ty;
+
+        // tuple structs are rewritten into the same named `data` field
+        node.fields = syn::Fields::Named(parse_quote! {
+            {
+              data: #storage_ty,
+            }
+        });
+        node.semi_token = None;
         node.attrs.push(parse_quote! {
             #[repr(C)]
         });
     }
 }
 
-fn get_total_bit_width(ts: &syn::ItemStruct) -> proc_macro2::TokenStream {
-    if let syn::Fields::Named(syn::FieldsNamed {
-        named: ref fields, ..
-    }) = ts.fields
-    {
-        fields.iter().fold(quote! {0}, |acc, f| {
-            let ty = &f.ty;
-            quote! {#acc + <#ty as Specifier>::BITS}
+// bit offset of every field, as const expressions summing up the widths of the
+// fields before it
+fn get_field_offsets(fields: &[BitfieldField]) -> Vec<proc_macro2::TokenStream> {
+    fields
+        .iter()
+        .scan(quote! {0}, |offset, f| {
+            // explicit positions have been checked to leave no gaps
+            let prev_offset = match f.position {
+                Some(ref position) => {
+                    let lo = position.lo;
+                    quote! {#lo}
+                }
+                None => offset.clone(),
+            };
+            let bit_width = f.bit_width();
+            *offset = quote! {#offset + #bit_width};
+            Some(prev_offset)
         })
-    } else {
-        unreachable!()
+        .collect()
+}
+
+fn build_field_infos(
+    fields: &[BitfieldField],
+    offsets: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    let infos = fields.iter().zip(offsets).map(|(f, offset)| {
+        let name = &f.name;
+        let ty_name = type_name(f.ty);
+        let bit_width = f.bit_width();
+        let len = match f.len {
+            Some(len) => quote! {::core::option::Option::Some(#len)},
+            None => quote! {::core::option::Option::None},
+        };
+        quote! {
+            ::bitfield::FieldInfo {
+                name: #name,
+                offset: #offset,
+                width: #bit_width,
+                ty: #ty_name,
+                len: #len,
+            }
+        }
+    });
+    quote! {
+        pub const FIELDS: &'static [::bitfield::FieldInfo] = &[#(#infos),*];
     }
 }
 
-fn sanity_check(ts: &syn::ItemStruct) -> proc_macro2::TokenStream {
-    let bit_width = get_total_bit_width(&ts);
-    let bit_tag_check = if let syn::Fields::Named(syn::FieldsNamed {
-        named: ref fields, ..
-    }) = ts.fields
-    {
-        fields.iter().filter_map(|f| {
-            if f.attrs.len() > 0 {
-                assert_eq!(f.attrs.len(), 1);
-                let bit_tag = &f.attrs[0];
-                let ty = &f.ty;
-                assert!(bit_tag.path().is_ident("bits"));
-                if let syn::Meta::NameValue(syn::MetaNameValue { ref value, .. }) = bit_tag.meta {
-                    let tagged_width = syn_expr_to_usize(&value).unwrap();
-                    let check = quote! {
-                        if #tagged_width != (<#ty as Specifier>::BITS as usize) {
-                            panic!("tagged bit does not align with the underlying bit width");
-                        }
-                    };
-                    return Some(check);
-                } else {
-                    unreachable!("invalid inner `bitfield` tag format")
-                }
+// the type as written, e.g. `Option<NonZeroU8>`: the tokens are joined
+// without the spaces `to_string` puts between all of them, except between two
+// words and after a comma or semicolon
+fn type_name(ty: &syn::Type) -> String {
+    let spaced = quote! {#ty}.to_string();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut name = String::new();
+    let mut chars = spaced.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ' ' {
+            name.push(c);
+            continue;
+        }
+        let prev = name.chars().last();
+        let next = chars.peek().copied();
+        let between_words = prev.is_some_and(is_word) && next.is_some_and(is_word);
+        if between_words || matches!(prev, Some(',' | ';')) {
+            name.push(' ');
+        }
+    }
+    name
+}
+
+fn get_total_bit_width(fields: &[BitfieldField]) -> proc_macro2::TokenStream {
+    fields.iter().fold(quote! {0}, |acc, f| {
+        let bit_width = f.bit_width();
+        quote! {#acc + #bit_width}
+    })
+}
+
+fn sanity_check(
+    fields: &[BitfieldField],
+    bit_width: &proc_macro2::TokenStream,
+    args: &BitfieldArgs,
+) -> proc_macro2::TokenStream {
+    // a wrong #[bits = N] or #[bits(lo..=hi)] makes the array lengths
+    // disagree, which the compiler reports on the attribute together with the
+    // actual width
+    let bit_tag_check = fields.iter().filter_map(|f| {
+        let field_width = f.bit_width();
+        let (tagged_width, span) = match (f.tagged_width, &f.position) {
+            (Some(tagged_width), _) => (quote! {#tagged_width}, tagged_width.span()),
+            (None, Some(position)) => {
+                let span = position.range.span();
+                let mut width = proc_macro2::Literal::usize_unsuffixed(position.width());
+                width.set_span(span);
+                (quote! {#width}, span)
             }
-            None
+            (None, None) => return None,
+        };
+        Some(quote_spanned! {span=>
+            let _: [(); #tagged_width] = [(); #field_width];
         })
-    } else {
-        unreachable!()
-    };
+    });
+
+    // the accessors move field values around as u128
+    let field_width_check = fields.iter().map(|f| {
+        let ty = f.ty;
+        let msg = format!("field `{}` is wider than 128 bits", f.name);
+        quote_spanned! {ty.span()=>
+            if <#ty as Specifier>::BITS > 128 {
+                panic!(#msg);
+            }
+        }
+    });
+
+    // unfilled bitfields are padded to whole bytes instead, and integer
+    // storage has to be covered exactly
+    let repr_check = args.repr.as_ref().map(|repr| {
+        quote_spanned! {repr.span()=>
+            const _: () = {
+                let _: [(); #repr::BITS as usize] = [(); #bit_width];
+            };
+        }
+    });
+    let total_size_check = (args.filled && args.repr.is_none()).then(|| {
+        quote! {
+            const _: () = {
+                fn total_size_is_multiple_of_eight_bits<
+                    T: ::bitfield::checks::TotalSizeIsMultipleOfEightBits,
+                >() {
+                }
+                let _ = total_size_is_multiple_of_eight_bits::<
+                    <[(); (#bit_width) % 8] as ::bitfield::checks::RemainderMod8>::Remainder,
+                >;
+            };
+        }
+    });
+
+    // there are no atomics wider than 64 bits to store the bitfield in
+    let atomic_check = args.atomic.as_ref().map(|atomic| {
+        quote_spanned! {atomic.span()=>
+            const _: () = {
+                if (#bit_width) > 64 {
+                    panic!("atomic bitfields must be at most 64 bits wide");
+                }
+            };
+        }
+    });
+
+    // `bytes = N` / `bits = N` are checked the same way as #[bits = N]
+    let byte_size = args.byte_size(bit_width);
+    let bytes_check = args.bytes.as_ref().map(|bytes| {
+        quote_spanned! {bytes.span()=>
+            let _: [(); #bytes] = [(); #byte_size];
+        }
+    });
+    let bits_check = args.bits.as_ref().map(|bits| {
+        quote_spanned! {bits.span()=>
+            let _: [(); #bits] = [(); #bit_width];
+        }
+    });
 
     quote! {
+        #repr_check
+        #atomic_check
+        #total_size_check
         const _: () = {
-            if (#bit_width) % 8 != 0 {panic!("sum of bit width is not divisive by 8");}
+            #bytes_check
+            #bits_check
         };
         const _: () = {
             #(#bit_tag_check)*
         };
+        const _: () = {
+            #(#field_width_check)*
+        };
     }
 }
 
-
-
-/// blanket impl for inner #[bit = xxx] attribute
-// #[proc_macro_attribute]
-// pub fn bits(_: TokenStream, input: TokenStream) -> TokenStream {input}
-
+/// bitfield
+///
+/// `#[bitfield]` has to come before `#[derive(Debug)]`: derives listed above
+/// it are expanded on the struct as written and never seen by the macro.
 #[proc_macro_attribute]
-pub fn bitfield(_: TokenStream, input: TokenStream) -> TokenStream {
-    let mut annot_struct = parse_macro_input!(input as syn::ItemStruct);
+pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut bitfield_args = BitfieldArgs::default();
+    let args_parser = syn::meta::parser(|meta| bitfield_args.parse(meta));
+    parse_macro_input!(args with args_parser);
+    let annot_struct = parse_macro_input!(input as syn::ItemStruct);
+    expand_bitfield(annot_struct, &bitfield_args)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_bitfield(
+    mut annot_struct: syn::ItemStruct,
+    bitfield_args: &BitfieldArgs,
+) -> syn::Result<proc_macro2::TokenStream> {
     let struct_name = &annot_struct.ident.clone();
 
-    let accessors = build_accessors(&annot_struct);
-    let bit_width = get_total_bit_width(&annot_struct);
+    let derive_debug = take_derive_debug(&mut annot_struct.attrs)?;
+    let fields = bitfield_fields(&annot_struct)?;
+    let offsets = get_field_offsets(&fields);
+    let accessors = build_accessors(&fields, &offsets, bitfield_args, Accessed::Owned);
+    let bit_width = get_total_bit_width(&fields);
+    let byte_size = bitfield_args.byte_size(&bit_width);
+    let storage_zero = bitfield_args.storage_zero(&byte_size);
+    let conversions = build_conversions(struct_name, &bit_width, bitfield_args);
+    let specifier_impl =
+        build_specifier_impl(struct_name, &annot_struct.vis, &bit_width, bitfield_args);
+    let layout = build_layout(&fields, &offsets, bitfield_args);
+    let field_infos = build_field_infos(&fields, &offsets);
+    let atomic = bitfield_args.atomic.is_some().then(|| {
+        build_atomic(
+            struct_name,
+            &annot_struct.vis,
+            &fields,
+            &offsets,
+            &bit_width,
+            bitfield_args,
+        )
+    });
+    let views = bitfield_args.views.then(|| {
+        build_views(
+            struct_name,
+            &annot_struct.vis,
+            &fields,
+            &offsets,
+            &byte_size,
+            bitfield_args,
+        )
+    });
+    let debug_impl = derive_debug.then(|| {
+        let tuple = matches!(annot_struct.fields, syn::Fields::Unnamed(_));
+        build_debug_impl(struct_name, &fields, tuple)
+    });
 
     // check sanity of the bitfield struct
     // 1. sum of bit width
     // 2. bit annotation aligns with the actual bit width constant in Specifier
-    let checker = sanity_check(&annot_struct);
-    BitfieldVisit.visit_item_struct_mut(&mut annot_struct);
+    // 3. every field fits in the u128 used by the accessors
+    let checker = sanity_check(&fields, &bit_width, bitfield_args);
+    drop(fields);
+    BitfieldVisit {
+        storage_ty: bitfield_args.storage_ty(&byte_size),
+    }
+    .visit_item_struct_mut(&mut annot_struct);
 
-    quote! {
+    Ok(quote! {
         #checker
         #annot_struct
         impl #struct_name{
-            pub fn new() -> Self {
-                Self {data: [0; (#bit_width)/8]}
-            }
+            #field_infos
 
-            fn fetch_bit(&self, bit_index: usize) -> u64 {
-                let byte_index: usize = bit_index / 8;
-                let offset: usize = 7 - (bit_index % 8);
-                assert!(byte_index < self.data.len());
-                ((self.data[byte_index] >> offset) & 0x1) as u64
+            pub const fn new() -> Self {
+                Self {data: #storage_zero}
             }
 
-            fn set_bit(&mut self, bit_index: usize, bit_val: u8) {
-                let byte_index: usize = bit_index / 8;
-                let offset: usize = 7 - (bit_index % 8);
-                assert!(byte_index < self.data.len());
-                self.data[byte_index] &= !(1 << offset);
-                self.data[byte_index] |= (bit_val << offset);
-            }
-
-            pub fn display(&self) {
-                let str_repr = self.data.map(|i| format!("{:08b}", i)).as_slice().join(" | ");
-                println!("{}", str_repr);
-            }
+            #layout
 
             #accessors
         }
-    }
-    .into()
+
+        #conversions
+        #specifier_impl
+        #debug_impl
+        #atomic
+        #views
+    })
 }
 
 fn syn_expr_to_usize(input: &syn::Expr) -> Option<usize> {
@@ -208,51 +1014,155 @@ fn syn_expr_to_usize(input: &syn::Expr) -> Option<usize> {
     }
 }
 
+fn find_best_fit_width(num_bit: usize) -> usize {
+    let idx = WIDTH_PTYPE
+        .as_slice()
+        .iter()
+        .position(|width| *width >= num_bit)
+        .unwrap();
+    WIDTH_PTYPE[idx]
+}
+
 fn find_best_fit_ty(num_bit: usize) -> syn::Ident {
-    let type_suffix = {
-        let idx = WIDTH_PTYPE
-            .as_slice()
-            .iter()
-            .position(|width| *width >= num_bit)
-            .unwrap();
-        WIDTH_PTYPE[idx]
-    };
+    let type_suffix = find_best_fit_width(num_bit);
     syn::Ident::new(&format!("u{}", type_suffix), proc_macro2::Span::call_site())
 }
 
+fn find_best_fit_signed_ty(num_bit: usize) -> syn::Ident {
+    let type_suffix = find_best_fit_width(num_bit);
+    syn::Ident::new(&format!("i{}", type_suffix), proc_macro2::Span::call_site())
+}
 
-#[proc_macro]
-pub fn specify_bits(ts: TokenStream) -> TokenStream {
-    let bit_range = parse_macro_input!(ts as syn::ExprRange);
+fn parse_bit_range(bit_range: syn::ExprRange) -> std::ops::Range<usize> {
     let start = bit_range
         .start
         .map_or(0usize, |s| syn_expr_to_usize(&s).unwrap());
-    let inclusive = if let syn::RangeLimits::Closed(_) = bit_range.limits {
-        true
-    } else {
-        false
-    };
+    let inclusive = matches!(bit_range.limits, syn::RangeLimits::Closed(_));
     let end = bit_range.end.and_then(|s| syn_expr_to_usize(&s)).unwrap();
-    let bit_range = std::ops::Range {
+    std::ops::Range {
         start,
         end: if inclusive { end + 1 } else { end },
-    };
+    }
+}
+
+
+#[proc_macro]
+pub fn specify_bits(ts: TokenStream) -> TokenStream {
+    let bit_range = parse_bit_range(parse_macro_input!(ts as syn::ExprRange));
     let trait_impl = bit_range.map(|i| {
         let ident = syn::Ident::new(&format!("{}{}", "B", i), proc_macro2::Span::call_site());
         let container_ty = find_best_fit_ty(i);
+        let codec_trait = codec_trait_impl(
+            &quote! {crate},
+            &quote! {#ident},
+            &quote! {#container_ty},
+            &quote! {#container_ty},
+        );
         quote! {
             pub enum #ident {}
+            #codec_trait
             impl Specifier for #ident {
                 const BITS: usize = #i;
                 type Container = #container_ty;
                 type Target = #container_ty;
+                type Codec = Self;
+            }
+            impl #ident {
+                pub const fn from_bit_repr(repr: #container_ty) -> #container_ty {
+                    repr
+                }
+
+                pub const fn try_from_bit_repr(
+                    repr: #container_ty,
+                ) -> ::core::result::Result<#container_ty, InvalidBitPattern<#container_ty>> {
+                    ::core::result::Result::Ok(repr)
+                }
+
+                pub const fn from_target(target: #container_ty) -> #container_ty {
+                    target
+                }
+            }
+        }
+    });
+    quote! {
+        #(#trait_impl)*
+    }
+    .into()
+}
+
+// two's complement conversions of a `bits` wide value kept in the low bits of
+// `container`, which is `container_bits` wide: the bodies of `from_bit_repr`,
+// sign extending `repr` into `target_ty`, and of `from_target`, cutting
+// `target` down. Out of range values get the bit above the field set, which
+// makes the setters' range check fail
+fn signed_conversions(
+    bits: usize,
+    container_bits: usize,
+    container_ty: &syn::Ident,
+    target_ty: &syn::Ident,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let unused = container_bits - bits;
+    let from_bit_repr = quote! {((repr << #unused) as #target_ty) >> #unused};
+    let from_target = if unused == 0 {
+        quote! {target as #container_ty}
+    } else {
+        let min = -(1i128 << (bits - 1));
+        let max = (1i128 << (bits - 1)) - 1;
+        let (min, max) = (
+            proc_macro2::Literal::i128_unsuffixed(min),
+            proc_macro2::Literal::i128_unsuffixed(max),
+        );
+        quote! {
+            let repr = (target as #container_ty) & (#container_ty::MAX >> #unused);
+            if target < #min || target > #max {
+                repr | (1 << #bits)
+            } else {
+                repr
+            }
+        }
+    };
+    (from_bit_repr, from_target)
+}
+
+// two's complement specifiers: the bit repr is kept in the unsigned container
+// and sign extended into the signed primitive of the same size
+#[proc_macro]
+pub fn specify_signed_bits(ts: TokenStream) -> TokenStream {
+    let bit_range = parse_bit_range(parse_macro_input!(ts as syn::ExprRange));
+    let trait_impl = bit_range.map(|i| {
+        let ident = syn::Ident::new(&format!("{}{}", "I", i), proc_macro2::Span::call_site());
+        let container_ty = find_best_fit_ty(i);
+        let target_ty = find_best_fit_signed_ty(i);
+        let (from_bit_repr, from_target) =
+            signed_conversions(i, find_best_fit_width(i), &container_ty, &target_ty);
+        let codec_trait = codec_trait_impl(
+            &quote! {crate},
+            &quote! {#ident},
+            &quote! {#container_ty},
+            &quote! {#target_ty},
+        );
+        quote! {
+            pub enum #ident {}
+            #codec_trait
+            impl Specifier for #ident {
+                const BITS: usize = #i;
+                type Container = #container_ty;
+                type Target = #target_ty;
+                type Codec = Self;
+            }
+            impl #ident {
+                pub const fn from_bit_repr(repr: #container_ty) -> #target_ty {
+                    #from_bit_repr
+                }
 
-                fn from_bit_repr(repr: Self::Container) -> Self::Target {
-                    repr as Self::Target
+                pub const fn try_from_bit_repr(
+                    repr: #container_ty,
+                ) -> ::core::result::Result<#target_ty, InvalidBitPattern<#container_ty>> {
+                    ::core::result::Result::Ok(Self::from_bit_repr(repr))
                 }
 
-                fn from_target(target: Self::Target) -> Self::Container {
-                    target as Self::Container
+                pub const fn from_target(target: #target_ty) -> #container_ty {
+                    #from_target
                 }
             }
         }
@@ -264,7 +1174,7 @@ pub fn specify_bits(ts: TokenStream) -> TokenStream {
 }
 
 /// BitfieldSpecifier
-#[proc_macro_derive(BitfieldSpecifier)]
+#[proc_macro_derive(BitfieldSpecifier, attributes(bits))]
 pub fn enum_specifier(ts: TokenStream) -> TokenStream {
     specifier::enum_specifier(ts)
 }
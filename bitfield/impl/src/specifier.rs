@@ -1,73 +1,313 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput};
 
 pub fn enum_specifier(ts: TokenStream) -> TokenStream {
     let ts = parse_macro_input!(ts as DeriveInput);
-    if let syn::Data::Enum(ref inner_enum) = ts.data {
-        let num_variant: usize = inner_enum.variants.len();
-        // check whether num_variant is power of 2
-        if !num_variant.is_power_of_two() {
-            return quote! {
-                std::compile_error!("num variant is not power of 2");
-            }
-            .into();
+    if !ts.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &ts.generics,
+            "BitfieldSpecifier cannot be derived for generic types",
+        )
+        .into_compile_error()
+        .into();
+    }
+    match ts.data {
+        syn::Data::Enum(ref inner_enum) => expand_enum_specifier(&ts, inner_enum),
+        syn::Data::Struct(ref inner_struct) => expand_newtype_specifier(&ts, inner_struct),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(
+            &ts.ident,
+            "BitfieldSpecifier can only be derived for enums and single field structs",
+        )),
+    }
+    .unwrap_or_else(syn::Error::into_compile_error)
+    .into()
+}
+
+fn expand_enum_specifier(
+    ts: &DeriveInput,
+    inner_enum: &syn::DataEnum,
+) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some(variant) = inner_enum
+        .variants
+        .iter()
+        .find(|variant| !variant.fields.is_empty())
+    {
+        return Err(syn::Error::new_spanned(
+            &variant.fields,
+            "BitfieldSpecifier enums cannot have variants with fields",
+        ));
+    }
+
+    let num_variant: usize = inner_enum.variants.len();
+    let tagged_width = tagged_bit_width(&ts.attrs, 64)?;
+    // without an explicit #[bits = N] the variants have to cover every bit
+    // pattern, i.e. num_variant has to be a power of 2
+    let num_bit_required = match tagged_width {
+        Some(width) => width,
+        None if num_variant.is_power_of_two() => num_variant.trailing_zeros() as usize,
+        None => {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                "BitfieldSpecifier expected a number of variants which is a power of 2",
+            ))
+        }
+    };
+    let enum_ident = &ts.ident;
+    let container_ty = super::find_best_fit_ty(num_bit_required);
+
+    // internal bit repr for each enum variant is its order within the enum
+    let disc_const_idents: Vec<_> = (0..num_variant)
+        .map(|i| syn::Ident::new(&format!("V{}", i), proc_macro2::Span::call_site()))
+        .collect();
+    let enum_variant_full_ident: Vec<_> = inner_enum
+        .variants
+        .iter()
+        .map(|variant| {
+            let variant_ident = variant.ident.clone();
+            quote! {#enum_ident::#variant_ident}
+        })
+        .collect();
+    // reported on the offending variant as an unsatisfied trait bound
+    let disc_range_check = inner_enum.variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote_spanned! {variant_ident.span()=>
+            let _ = discriminant_in_range::<
+                <[(); ((#enum_ident::#variant_ident as u128) < (1u128 << #num_bit_required)) as usize]
+                    as ::bitfield::checks::Bool>::Value,
+            >;
         }
-        let enum_ident = &ts.ident;
-        let num_bit_required = num_variant.trailing_zeros() as usize;
-        let container_ty = super::find_best_fit_ty(num_bit_required);
-
-        // internal bit repr for each enum variant is its order within the enum
-        let disc_const_idents: Vec<_> = (0..num_variant)
-            .map(|i| syn::Ident::new(&format!("V{}", i), proc_macro2::Span::call_site()))
-            .collect();
-        let enum_variant_full_ident: Vec<_> = inner_enum
-            .variants
-            .iter()
-            .map(|variant| {
-                let variant_ident = variant.ident.clone();
-                quote! {#enum_ident::#variant_ident}
-            })
-            .collect();
-        let disc_range_check = {
-            let check_stmt = enum_variant_full_ident.iter().map(|variant_ident| {
-                quote! {
-                    if (#variant_ident as usize) >= (1 << #num_bit_required) {
-                        panic!("user specified invalid discriminater");
+    });
+
+    // the conversions live on a codec type that is only reachable through
+    // `<#enum_ident as Specifier>::Codec`, so that they do not show up as
+    // inherent methods on the user's enum. It is as visible as the enum,
+    // which its `Codec` impl exposes
+    let vis = &ts.vis;
+    let codec_ident = format_ident!("{}Codec", enum_ident);
+    let codec_trait = super::codec_trait_impl(
+        &quote! {::bitfield},
+        &quote! {#codec_ident},
+        &quote! {#container_ty},
+        &quote! {#enum_ident},
+    );
+    Ok(quote! {
+        const _: () = {
+            fn discriminant_in_range<T: ::bitfield::checks::DiscriminantInRange>() {}
+            #(#disc_range_check)*
+        };
+
+        const _: () = {
+            #vis enum #codec_ident {}
+
+            #codec_trait
+
+            impl #codec_ident {
+                pub const fn from_bit_repr(repr: #container_ty) -> #enum_ident {
+                    match Self::try_from_bit_repr(repr) {
+                        Ok(target) => target,
+                        Err(_) => panic!("invalid enum discrimant"),
                     }
                 }
-            });
-            quote! {
-                const _: () = {#(#check_stmt)*};
+
+                pub const fn try_from_bit_repr(
+                    repr: #container_ty,
+                ) -> Result<#enum_ident, ::bitfield::InvalidBitPattern<#container_ty>> {
+                    // instead of parsing the discrimant from the enum token tree itself
+                    // use rust internal discrimant repr for a variant
+
+                    // const list
+                    #(const #disc_const_idents: #container_ty = #enum_variant_full_ident as #container_ty;)*
+                    match repr {
+                        #(#disc_const_idents => Ok(#enum_variant_full_ident)),*,
+                        _ => Err(::bitfield::InvalidBitPattern { raw: repr }),
+                    }
+                }
+
+                pub const fn from_target(target: #enum_ident) -> #container_ty {
+                    target as #container_ty
+                }
             }
-        };
 
-        quote! {
             impl Specifier for #enum_ident {
                 const BITS: usize = #num_bit_required;
                 type Container = #container_ty;
                 type Target = #enum_ident;
+                type Codec = #codec_ident;
+            }
+        };
+    })
+}
 
-                fn from_bit_repr(repr: Self::Container) -> Self::Target {
-                    // instead of parsing the discrimant from the enum token tree itself
-                    // use rust internal discrimant repr for a variant
+// a newtype over another specifier, e.g. `struct Port(#[bits = 12] u16);`.
+// The bit repr is that of the inner type, cut down to N bits if given; values
+// that do not fit are caught by the range check of the setters. A signed
+// primitive cut down to N bits is an N bit two's complement value like `I<N>`
+fn expand_newtype_specifier(
+    ts: &DeriveInput,
+    inner_struct: &syn::DataStruct,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut fields = inner_struct.fields.iter();
+    let field = match (fields.next(), fields.next()) {
+        (Some(field), None) => field,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ts.ident,
+                "BitfieldSpecifier can only be derived for enums and single field structs",
+            ))
+        }
+    };
+    let struct_ident = &ts.ident;
+    let inner_ty = &field.ty;
+    let member = match field.ident {
+        Some(ref ident) => quote! {#ident},
+        None => quote! {0},
+    };
 
-                    // const list
-                    #disc_range_check
-                    #(const #disc_const_idents: <#enum_ident as Specifier>::Container = #enum_variant_full_ident as <#enum_ident as Specifier>::Container;)*
-                    match repr {
-                        #(#disc_const_idents => #enum_variant_full_ident),*,
-                        _ => unreachable!("invalid enum discrimant")
-                    }
+    // #[bits = N] may sit on the struct or on its field, but not on both
+    let attrs: Vec<_> = ts.attrs.iter().chain(&field.attrs).cloned().collect();
+    let tagged_width = tagged_bit_width(&attrs, 128)?;
+    let bit_width = match tagged_width {
+        Some(width) => quote! {#width},
+        None => quote! {<#inner_ty as Specifier>::BITS},
+    };
+    let width_check = tagged_bit_width_span(&attrs).map(|span| {
+        let msg = format!(
+            "#[bits = N] is wider than `{}`",
+            quote! {#inner_ty}.to_string().replace(' ', "")
+        );
+        quote_spanned! {span=>
+            const _: () = {
+                if #bit_width > <#inner_ty as Specifier>::BITS {
+                    panic!(#msg);
                 }
-                fn from_target(target: Self::Target) -> Self::Container {
-                    target as Self::Container
+            };
+        }
+    });
+
+    let vis = &ts.vis;
+    let codec_ident = format_ident!("{}Codec", struct_ident);
+    let container_ty = quote! {<#inner_ty as Specifier>::Container};
+    let inner_codec = quote! {<<#inner_ty as Specifier>::Codec>};
+    let codec_trait = super::codec_trait_impl(
+        &quote! {::bitfield},
+        &quote! {#codec_ident},
+        &container_ty,
+        &quote! {#struct_ident},
+    );
+    let narrowed_signed = tagged_width.and_then(|width| {
+        let (target_ty, inner_width) = signed_primitive(inner_ty)?;
+        let container_ty = format_ident!("u{}", inner_width);
+        (width > 0 && width < inner_width)
+            .then(|| super::signed_conversions(width, inner_width, &container_ty, &target_ty))
+    });
+    let conversions = match narrowed_signed {
+        Some((from_bit_repr, from_target)) => quote! {
+            pub const fn from_bit_repr(repr: #container_ty) -> #struct_ident {
+                #struct_ident { #member: #from_bit_repr }
+            }
+
+            pub const fn try_from_bit_repr(
+                repr: #container_ty,
+            ) -> ::core::result::Result<#struct_ident, ::bitfield::InvalidBitPattern<#container_ty>> {
+                ::core::result::Result::Ok(Self::from_bit_repr(repr))
+            }
+
+            pub const fn from_target(target: #struct_ident) -> #container_ty {
+                let target = target.#member;
+                #from_target
+            }
+        },
+        None => quote! {
+            pub const fn from_bit_repr(repr: #container_ty) -> #struct_ident {
+                #struct_ident { #member: #inner_codec::from_bit_repr(repr) }
+            }
+
+            pub const fn try_from_bit_repr(
+                repr: #container_ty,
+            ) -> ::core::result::Result<#struct_ident, ::bitfield::InvalidBitPattern<#container_ty>> {
+                match #inner_codec::try_from_bit_repr(repr) {
+                    ::core::result::Result::Ok(inner) => {
+                        ::core::result::Result::Ok(#struct_ident { #member: inner })
+                    }
+                    ::core::result::Result::Err(err) => ::core::result::Result::Err(err),
                 }
+            }
+
+            pub const fn from_target(target: #struct_ident) -> #container_ty {
+                #inner_codec::from_target(target.#member)
+            }
+        },
+    };
+    Ok(quote! {
+        #width_check
+
+        const _: () = {
+            #vis enum #codec_ident {}
+
+            #codec_trait
+
+            impl #codec_ident {
+                #conversions
+            }
 
+            impl Specifier for #struct_ident {
+                const BITS: usize = #bit_width;
+                type Container = #container_ty;
+                type Target = #struct_ident;
+                type Codec = #codec_ident;
+            }
+        };
+    })
+}
+
+// `i8` ..= `i128` with their width, the inner types whose bit repr has to be
+// sign extended when cut down
+fn signed_primitive(ty: &syn::Type) -> Option<(syn::Ident, usize)> {
+    let ident = match ty {
+        syn::Type::Path(path) => &path.path.segments.last()?.ident,
+        _ => return None,
+    };
+    let width = match ident.to_string().as_str() {
+        "i8" => 8,
+        "i16" => 16,
+        "i32" => 32,
+        "i64" => 64,
+        "i128" => 128,
+        _ => return None,
+    };
+    Some((ident.clone(), width))
+}
+
+fn tagged_bit_width_span(attrs: &[syn::Attribute]) -> Option<proc_macro2::Span> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("bits"))
+        .and_then(|attr| attr.meta.require_name_value().ok())
+        .map(|meta| meta.value.span())
+}
+
+// optional #[bits = N] fixing the bit width, at most `max`
+fn tagged_bit_width(attrs: &[syn::Attribute], max: usize) -> syn::Result<Option<usize>> {
+    let mut tagged_width = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("bits")) {
+        if tagged_width.is_some() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "duplicate #[bits = N] attribute",
+            ));
+        }
+        let value = &attr.meta.require_name_value()?.value;
+        match super::syn_expr_to_usize(value) {
+            Some(width) if width <= max => tagged_width = Some(width),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    value,
+                    format!("expected an integer literal between 0 and {}", max),
+                ))
             }
         }
-        .into()
-    } else {
-        unreachable!()
     }
+    Ok(tagged_width)
 }
@@ -0,0 +1,67 @@
+// Compile time checks of the code emitted by #[bitfield] and
+// #[derive(BitfieldSpecifier)]. They are expressed as trait bounds rather than
+// panics in const items so that the compiler error names what went wrong, e.g.
+//
+//     the trait bound `SevenMod8: TotalSizeIsMultipleOfEightBits` is not satisfied
+
+pub trait TotalSizeIsMultipleOfEightBits {}
+
+pub enum ZeroMod8 {}
+pub enum OneMod8 {}
+pub enum TwoMod8 {}
+pub enum ThreeMod8 {}
+pub enum FourMod8 {}
+pub enum FiveMod8 {}
+pub enum SixMod8 {}
+pub enum SevenMod8 {}
+
+impl TotalSizeIsMultipleOfEightBits for ZeroMod8 {}
+
+/// Maps `[(); N % 8]` to one of the `*Mod8` types.
+pub trait RemainderMod8 {
+    type Remainder;
+}
+
+impl RemainderMod8 for [(); 0] {
+    type Remainder = ZeroMod8;
+}
+impl RemainderMod8 for [(); 1] {
+    type Remainder = OneMod8;
+}
+impl RemainderMod8 for [(); 2] {
+    type Remainder = TwoMod8;
+}
+impl RemainderMod8 for [(); 3] {
+    type Remainder = ThreeMod8;
+}
+impl RemainderMod8 for [(); 4] {
+    type Remainder = FourMod8;
+}
+impl RemainderMod8 for [(); 5] {
+    type Remainder = FiveMod8;
+}
+impl RemainderMod8 for [(); 6] {
+    type Remainder = SixMod8;
+}
+impl RemainderMod8 for [(); 7] {
+    type Remainder = SevenMod8;
+}
+
+pub trait DiscriminantInRange {}
+
+pub enum True {}
+pub enum False {}
+
+impl DiscriminantInRange for True {}
+
+/// Maps `[(); CONDITION as usize]` to `True` or `False`.
+pub trait Bool {
+    type Value;
+}
+
+impl Bool for [(); 0] {
+    type Value = False;
+}
+impl Bool for [(); 1] {
+    type Value = True;
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Returned when building a bitfield from a byte slice whose length does not
+/// match the size of the bitfield.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthMismatch {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected a slice of {} bytes, found {} bytes",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for LengthMismatch {}
+
+/// Returned by the checked setters when a value does not fit into the bit
+/// width of its field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfBounds {
+    pub bits: usize,
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value does not fit into {} bits", self.bits)
+    }
+}
+
+impl std::error::Error for OutOfBounds {}
+
+/// Returned by the fallible getters when the stored bits do not correspond to
+/// any value of the field's type. The raw bits are kept in `raw`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidBitPattern<T> {
+    pub raw: T,
+}
+
+impl<T: fmt::Binary> fmt::Display for InvalidBitPattern<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid bit pattern {:#b}", self.raw)
+    }
+}
+
+impl<T: fmt::Binary + fmt::Debug> std::error::Error for InvalidBitPattern<T> {}
@@ -0,0 +1,15 @@
+/// Description of one field of a #[bitfield] struct, as found in its
+/// `FIELDS` table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// Name of the field, or its position for tuple structs.
+    pub name: &'static str,
+    /// Offset of the first bit, counted in the bit order of the bitfield.
+    pub offset: usize,
+    /// Number of bits, covering all elements for array fields.
+    pub width: usize,
+    /// Specifier type as written, or that of each element for array fields.
+    pub ty: &'static str,
+    /// Number of elements for array fields.
+    pub len: Option<usize>,
+}
@@ -11,44 +11,190 @@
 // From the perspective of a user of this crate, they get all the necessary APIs
 // (macro, trait, struct) through the one bitfield crate.
 
-
-/// TODO: figure out how to better report error with correct span instead of just panic
-
 #[allow(unused_imports)]
 pub use bitfield_impl::{bitfield, BitfieldSpecifier};
 
+#[doc(hidden)]
+pub mod checks;
+mod error;
+mod info;
+#[doc(hidden)]
+pub mod private;
+mod volatile;
+
+pub use error::{InvalidBitPattern, LengthMismatch, OutOfBounds};
+pub use info::FieldInfo;
+pub use volatile::Volatile;
+
 pub trait Specifier {
     const BITS: usize;
     // minimal rust primitive type that contains the internal bit repr
     type Container;
     // target type the contain wants to coerse to
     type Target;
+    // type carrying the conversions between `Container` and `Target`. Trait
+    // methods cannot be called in const context, so the generated accessors
+    // call these inherent functions on the codec instead:
+    //
+    //     const fn from_bit_repr(repr: Container) -> Target;
+    //     const fn try_from_bit_repr(repr: Container)
+    //         -> Result<Target, InvalidBitPattern<Container>>;
+    //     const fn from_target(target: Target) -> Container;
+    //
+    // `try_from_bit_repr` is the fallible counterpart of `from_bit_repr` for
+    // specifiers whose bit repr does not cover every target value, e.g. data
+    // received from the wire.
+    //
+    // The codec also implements the `Codec` trait by forwarding to them, which
+    // lets the compiler check a hand-written codec at the `impl Specifier`
+    // and gives generic code a way to call the conversions. The trait cannot
+    // require the inherent functions, so a codec implementing only the trait
+    // gets past the impl and fails inside the `#[bitfield]` expansion instead;
+    // `codec!` writes both from one set of const fns.
+    type Codec: Codec<Container = Self::Container, Target = Self::Target>;
+}
+
+/// Non-const counterpart of the inherent functions on [`Specifier::Codec`],
+/// implemented by forwarding to them: inherent functions take precedence, so
+/// `Self::from_bit_repr(repr)` in the impl calls the const fn.
+pub trait Codec {
+    type Container;
+    type Target;
 
     fn from_bit_repr(repr: Self::Container) -> Self::Target;
+
+    fn try_from_bit_repr(
+        repr: Self::Container,
+    ) -> Result<Self::Target, InvalidBitPattern<Self::Container>>;
+
     fn from_target(target: Self::Target) -> Self::Container;
 }
 
-bitfield_impl::specify_bits!(0..=64);
+/// Defines the conversions of a hand-written codec once, as the inherent
+/// const fns the generated accessors call plus the [`Codec`] impl forwarding
+/// to them. A codec that only implements the trait compiles on its own, but
+/// fails with E0015 as soon as a `#[bitfield]` uses it.
+///
+/// ```ignore
+/// bitfield::codec! {
+///     impl Codec for Digit {
+///         type Container = u8;
+///         type Target = Digit;
+///
+///         const fn from_bit_repr(repr: u8) -> Digit { ... }
+///         const fn try_from_bit_repr(repr: u8) -> Result<Digit, InvalidBitPattern<u8>> { ... }
+///         const fn from_target(target: Digit) -> u8 { ... }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! codec {
+    (
+        impl Codec for $codec:ty {
+            type Container = $container:ty;
+            type Target = $target:ty;
+
+            $(#[$from_attr:meta])*
+            const fn from_bit_repr($from_arg:ident: $from_ty:ty) -> $from_ret:ty $from_body:block
+            $(#[$try_attr:meta])*
+            const fn try_from_bit_repr($try_arg:ident: $try_ty:ty) -> $try_ret:ty $try_body:block
+            $(#[$target_attr:meta])*
+            const fn from_target($target_arg:ident: $target_ty:ty) -> $target_ret:ty $target_body:block
+        }
+    ) => {
+        impl $codec {
+            $(#[$from_attr])*
+            pub const fn from_bit_repr($from_arg: $from_ty) -> $from_ret $from_body
+            $(#[$try_attr])*
+            pub const fn try_from_bit_repr($try_arg: $try_ty) -> $try_ret $try_body
+            $(#[$target_attr])*
+            pub const fn from_target($target_arg: $target_ty) -> $target_ret $target_body
+        }
+
+        impl $crate::Codec for $codec {
+            type Container = $container;
+            type Target = $target;
+
+            fn from_bit_repr(repr: $container) -> $target {
+                Self::from_bit_repr(repr)
+            }
+
+            fn try_from_bit_repr(
+                repr: $container,
+            ) -> ::core::result::Result<$target, $crate::InvalidBitPattern<$container>> {
+                Self::try_from_bit_repr(repr)
+            }
+
+            fn from_target(target: $target) -> $container {
+                Self::from_target(target)
+            }
+        }
+    };
+}
+
+bitfield_impl::specify_bits!(0..=128);
+bitfield_impl::specify_signed_bits!(1..=64);
 
 /// blanket impl for some rust primitives
 impl Specifier for bool {
     const BITS: usize = 1;
     type Container = u8;
     type Target = bool;
+    type Codec = private::BoolCodec;
+}
 
-    fn from_bit_repr(repr: Self::Container) -> Self::Target {
-        match repr {
-            0 => false,
-            1 => true,
-            _ => unreachable!("invalid internal repr for `bool`"),
-        }
-    }
+// integer primitives take up all of their bits; the signed ones keep their
+// two's complement as bit repr
+macro_rules! primitive_specifier {
+    ($($ty:ty: $container:ty, $codec:ident;)*) => {
+        $(
+            impl Specifier for $ty {
+                const BITS: usize = <$container>::BITS as usize;
+                type Container = $container;
+                type Target = $ty;
+                type Codec = private::$codec;
+            }
+        )*
+    };
+}
 
-    fn from_target(target: Self::Target) -> Self::Container {
-        if target {
-            1
-        } else {
-            0
-        }
-    }
+primitive_specifier! {
+    u8: u8, U8Codec;
+    u16: u16, U16Codec;
+    u32: u32, U32Codec;
+    u64: u64, U64Codec;
+    u128: u128, U128Codec;
+    i8: u8, I8Codec;
+    i16: u16, I16Codec;
+    i32: u32, I32Codec;
+    i64: u64, I64Codec;
+    i128: u128, I128Codec;
+    char: u32, CharCodec;
+}
+
+// the all zero bit pattern stands for `None`
+macro_rules! non_zero_specifier {
+    ($($ty:ident: $container:ty, $codec:ident;)*) => {
+        $(
+            impl Specifier for Option<core::num::$ty> {
+                const BITS: usize = <$container>::BITS as usize;
+                type Container = $container;
+                type Target = Option<core::num::$ty>;
+                type Codec = private::$codec;
+            }
+        )*
+    };
+}
+
+non_zero_specifier! {
+    NonZeroU8: u8, NonZeroU8Codec;
+    NonZeroU16: u16, NonZeroU16Codec;
+    NonZeroU32: u32, NonZeroU32Codec;
+    NonZeroU64: u64, NonZeroU64Codec;
+    NonZeroU128: u128, NonZeroU128Codec;
+    NonZeroI8: u8, NonZeroI8Codec;
+    NonZeroI16: u16, NonZeroI16Codec;
+    NonZeroI32: u32, NonZeroI32Codec;
+    NonZeroI64: u64, NonZeroI64Codec;
+    NonZeroI128: u128, NonZeroI128Codec;
 }
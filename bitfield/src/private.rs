@@ -0,0 +1,677 @@
+// Runtime support for the code emitted by #[bitfield]. Nothing in here is meant
+// to be called by hand; the generated accessors pass in offsets, widths and
+// layout choices that are constants, so after inlining the compiler folds each
+// call down to a couple of loads, shifts and masks. Everything is a const fn so
+// that the accessors can be const fns too.
+//
+// The storage is treated as one big unsigned integer whose bytes are laid out
+// in memory according to `Endian`. Field offsets count either down from the
+// most significant bit of that integer (`BitOrder::Msb0`) or up from its least
+// significant bit (`BitOrder::Lsb0`). With the default of Msb0 + Big, bit 0 is
+// `data[0] & 0x80` and bit 8 is `data[1] & 0x80`.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU16, AtomicU32, AtomicU64, AtomicU8, Ordering};
+
+use crate::InvalidBitPattern;
+
+/// Where the first field of a bitfield starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+    Msb0,
+    Lsb0,
+}
+
+/// Memory order of the bytes making up the storage integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endian {
+    Big,
+    Little,
+}
+
+// position of the field's least significant bit within the storage integer
+#[inline]
+const fn lsb_position(len: usize, offset: usize, width: usize, order: BitOrder) -> usize {
+    match order {
+        BitOrder::Msb0 => len * 8 - offset - width,
+        BitOrder::Lsb0 => offset,
+    }
+}
+
+// memory index of byte `i` of the storage integer, counted from its least
+// significant byte
+#[inline]
+const fn byte_index(len: usize, i: usize, endian: Endian) -> usize {
+    match endian {
+        Endian::Big => len - 1 - i,
+        Endian::Little => i,
+    }
+}
+
+// the 8 bytes of the storage integer starting at byte `lo`, as a native word
+#[inline]
+const fn load_word(data: &[u8], lo: usize, endian: Endian) -> u64 {
+    let mut word = 0u64;
+    let mut i = lo + 8;
+    while i > lo {
+        i -= 1;
+        word = (word << 8) | data[byte_index(data.len(), i, endian)] as u64;
+    }
+    word
+}
+
+#[inline]
+const fn store_word(data: &mut [u8], lo: usize, endian: Endian, mut word: u64) {
+    let mut i = lo;
+    while i < lo + 8 {
+        data[byte_index(data.len(), i, endian)] = word as u8;
+        word >>= 8;
+        i += 1;
+    }
+}
+
+// the `width` <= 64 bits whose least significant bit is at `lsb`
+#[inline]
+const fn read_word_bits(data: &[u8], lsb: usize, width: usize, endian: Endian) -> u64 {
+    let len = data.len();
+    let (lo, shift) = (lsb / 8, lsb % 8);
+    let mask = u64::MAX >> (64 - width);
+    if shift + width <= 64 && lo + 8 <= len {
+        // the field fits in one native word starting at its lowest byte
+        (load_word(data, lo, endian) >> shift) & mask
+    } else {
+        // near the end of the storage, or a wide field straddling nine bytes
+        let mut word = 0u128;
+        let mut i = (lsb + width - 1) / 8 + 1;
+        while i > lo {
+            i -= 1;
+            word = (word << 8) | data[byte_index(len, i, endian)] as u128;
+        }
+        (word >> shift) as u64 & mask
+    }
+}
+
+#[inline]
+const fn write_word_bits(data: &mut [u8], lsb: usize, width: usize, endian: Endian, value: u64) {
+    let len = data.len();
+    let (lo, shift) = (lsb / 8, lsb % 8);
+    let mask = u64::MAX >> (64 - width);
+    let value = value & mask;
+    if shift + width <= 64 && lo + 8 <= len {
+        let word = load_word(data, lo, endian);
+        let word = (word & !(mask << shift)) | (value << shift);
+        store_word(data, lo, endian, word);
+    } else {
+        let hi = (lsb + width - 1) / 8;
+        let mut word = 0u128;
+        let mut i = hi + 1;
+        while i > lo {
+            i -= 1;
+            word = (word << 8) | data[byte_index(len, i, endian)] as u128;
+        }
+        word = (word & !((mask as u128) << shift)) | ((value as u128) << shift);
+        while i <= hi {
+            data[byte_index(len, i, endian)] = word as u8;
+            word >>= 8;
+            i += 1;
+        }
+    }
+}
+
+/// Reads the `width` bits at `offset` from `data`.
+#[inline]
+pub const fn read_bits(
+    data: &[u8],
+    offset: usize,
+    width: usize,
+    order: BitOrder,
+    endian: Endian,
+) -> u128 {
+    if width == 0 {
+        return 0;
+    }
+    let lsb = lsb_position(data.len(), offset, width, order);
+    if width <= 64 {
+        read_word_bits(data, lsb, width, endian) as u128
+    } else {
+        // fields wider than a native word are read as two halves
+        let low = read_word_bits(data, lsb, 64, endian) as u128;
+        let high = read_word_bits(data, lsb + 64, width - 64, endian) as u128;
+        (high << 64) | low
+    }
+}
+
+/// Overwrites the `width` bits at `offset` in `data` with the low bits of
+/// `value`, leaving every other bit untouched.
+#[inline]
+pub const fn write_bits(
+    data: &mut [u8],
+    offset: usize,
+    width: usize,
+    order: BitOrder,
+    endian: Endian,
+    value: u128,
+) {
+    if width == 0 {
+        return;
+    }
+    let lsb = lsb_position(data.len(), offset, width, order);
+    if width <= 64 {
+        write_word_bits(data, lsb, width, endian, value as u64);
+    } else {
+        write_word_bits(data, lsb, 64, endian, value as u64);
+        write_word_bits(data, lsb + 64, width - 64, endian, (value >> 64) as u64);
+    }
+}
+
+// position of a field's least significant bit within the fields read as one
+// integer of `len` bits, as done for nesting and by the atomic companions
+#[inline]
+const fn int_lsb_position(len: usize, offset: usize, width: usize, order: BitOrder) -> usize {
+    match order {
+        BitOrder::Msb0 => len - offset - width,
+        BitOrder::Lsb0 => offset,
+    }
+}
+
+/// Reads the `width` bits at `offset` from the `len` bit integer `int`.
+#[inline]
+pub const fn read_int_bits(
+    int: u128,
+    len: usize,
+    offset: usize,
+    width: usize,
+    order: BitOrder,
+) -> u128 {
+    if width == 0 {
+        return 0;
+    }
+    let lsb = int_lsb_position(len, offset, width, order);
+    (int >> lsb) & (u128::MAX >> (128 - width))
+}
+
+/// Overwrites the `width` bits at `offset` in the `len` bit integer `int`
+/// with the low bits of `value`.
+#[inline]
+pub const fn write_int_bits(
+    int: u128,
+    len: usize,
+    offset: usize,
+    width: usize,
+    order: BitOrder,
+    value: u128,
+) -> u128 {
+    if width == 0 {
+        return int;
+    }
+    let lsb = int_lsb_position(len, offset, width, order);
+    let mask = u128::MAX >> (128 - width);
+    (int & !(mask << lsb)) | ((value & mask) << lsb)
+}
+
+/// Failure ordering of the compare-exchange loops of the atomic companions,
+/// the strongest one allowed for `order`.
+#[inline]
+pub const fn failure_ordering(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        order => order,
+    }
+}
+
+/// Atomic type backing the atomic companion of a bitfield whose fields read
+/// as one integer fit in `Self`.
+pub trait AtomicInt {
+    type Atomic;
+}
+
+impl AtomicInt for u8 {
+    type Atomic = AtomicU8;
+}
+impl AtomicInt for u16 {
+    type Atomic = AtomicU16;
+}
+impl AtomicInt for u32 {
+    type Atomic = AtomicU32;
+}
+impl AtomicInt for u64 {
+    type Atomic = AtomicU64;
+}
+
+/// Backing integer of a `#[bitfield(repr = uN)]` struct, implemented by the
+/// macro for those only.
+///
+/// # Safety
+///
+/// `Self` has to be `#[repr(C)]` around a single `Bits`, so that `Volatile`
+/// can load and store it through a `*mut Bits` in one access.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not backed by an integer",
+    note = "Volatile is only available for bitfields declared with `#[bitfield(repr = uN)]`"
+)]
+pub unsafe trait ReprBits {
+    type Bits: Copy;
+
+    fn from_bits(bits: Self::Bits) -> Self;
+
+    fn into_bits(self) -> Self::Bits;
+}
+
+/// Whether `value` is representable in `width` bits.
+#[inline]
+pub const fn fits(value: u128, width: usize) -> bool {
+    width >= 128 || value >> width == 0
+}
+
+/// Message of the setters' range assertion, "value does not fit into field
+/// `name` of N bits". Const fns cannot format a panic message, so it is put
+/// together in a constant; the value itself is only known at runtime and left
+/// out.
+pub struct OverflowMessage {
+    buf: [u8; 256],
+    len: usize,
+}
+
+impl OverflowMessage {
+    pub const fn new(field: &str, bits: usize) -> Self {
+        let msg = OverflowMessage {
+            buf: [0; 256],
+            len: 0,
+        };
+        msg.push("value does not fit into field `")
+            .push(field)
+            .push("` of ")
+            .push_usize(bits)
+            .push(" bits")
+    }
+
+    const fn push(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() && self.len < self.buf.len() {
+            self.buf[self.len] = bytes[i];
+            self.len += 1;
+            i += 1;
+        }
+        self
+    }
+
+    const fn push_usize(mut self, mut n: usize) -> Self {
+        let mut digits = [0u8; 20];
+        let mut count = 0;
+        loop {
+            digits[count] = b'0' + (n % 10) as u8;
+            count += 1;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        while count > 0 && self.len < self.buf.len() {
+            count -= 1;
+            self.buf[self.len] = digits[count];
+            self.len += 1;
+        }
+        self
+    }
+
+    pub const fn as_str(&self) -> &str {
+        // a name cut short in the middle of a character is not valid UTF-8
+        match core::str::from_utf8(self.buf.split_at(self.len).0) {
+            Ok(msg) => msg,
+            Err(_) => "value does not fit into field",
+        }
+    }
+}
+
+/// Copies `bytes` into an array of `N` bytes if the lengths agree.
+#[inline]
+pub const fn copy_exact<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+    if bytes.len() != N {
+        return None;
+    }
+    let mut data = [0; N];
+    let mut i = 0;
+    while i < N {
+        data[i] = bytes[i];
+        i += 1;
+    }
+    Some(data)
+}
+
+/// Views `bytes` as an array of `N` bytes if the lengths agree.
+#[inline]
+pub const fn ref_exact<const N: usize>(bytes: &[u8]) -> Option<&[u8; N]> {
+    if bytes.len() != N {
+        return None;
+    }
+    bytes.first_chunk()
+}
+
+/// Views `bytes` as a mutable array of `N` bytes if the lengths agree.
+#[inline]
+pub const fn mut_exact<const N: usize>(bytes: &mut [u8]) -> Option<&mut [u8; N]> {
+    if bytes.len() != N {
+        return None;
+    }
+    bytes.first_chunk_mut()
+}
+
+/// A field as shown by the `Debug` impl of a bitfield: its value, or the
+/// invalid bit pattern if the stored bits do not correspond to any value.
+pub struct DebugField<T, R>(pub Result<T, InvalidBitPattern<R>>);
+
+impl<T: fmt::Debug, R: fmt::Debug> fmt::Debug for DebugField<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Ok(ref value) => value.fmt(f),
+            Err(ref err) => err.fmt(f),
+        }
+    }
+}
+
+/// Codec of the `bool` specifier.
+pub enum BoolCodec {}
+
+impl BoolCodec {
+    pub const fn from_bit_repr(repr: u8) -> bool {
+        match repr {
+            0 => false,
+            1 => true,
+            _ => panic!("invalid internal repr for `bool`"),
+        }
+    }
+
+    pub const fn try_from_bit_repr(repr: u8) -> Result<bool, InvalidBitPattern<u8>> {
+        match repr {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(InvalidBitPattern { raw: repr }),
+        }
+    }
+
+    pub const fn from_target(target: bool) -> u8 {
+        target as u8
+    }
+}
+
+// forwards the `Codec` trait to the inherent const fns of a codec
+macro_rules! codec_trait {
+    ($($codec:ident: $container:ty => $target:ty;)*) => {
+        $(
+            impl crate::Codec for $codec {
+                type Container = $container;
+                type Target = $target;
+
+                fn from_bit_repr(repr: $container) -> $target {
+                    Self::from_bit_repr(repr)
+                }
+
+                fn try_from_bit_repr(
+                    repr: $container,
+                ) -> Result<$target, InvalidBitPattern<$container>> {
+                    Self::try_from_bit_repr(repr)
+                }
+
+                fn from_target(target: $target) -> $container {
+                    Self::from_target(target)
+                }
+            }
+        )*
+    };
+}
+
+codec_trait! {
+    BoolCodec: u8 => bool;
+}
+
+/// One box of the diagram drawn by `fmt_layout`, covering the bits of a field
+/// or of one element of an array field.
+pub struct LayoutField {
+    pub name: &'static str,
+    pub index: Option<usize>,
+    pub offset: usize,
+    pub width: usize,
+    // whether the value is shown, i.e. the field has a getter
+    pub readable: bool,
+}
+
+// `text` centered in `cols` columns, cut short if it does not fit
+fn centered(text: &str, cols: usize) -> String {
+    let text: String = text.chars().take(cols).collect();
+    format!("{:^cols$}", text)
+}
+
+// a row of boxes given as (bits, text), each bit taking up two columns
+fn text_row(w: &mut impl fmt::Write, cells: &[(usize, String)]) -> fmt::Result {
+    for (bits, text) in cells {
+        write!(w, "║{}", centered(text, 2 * bits - 1))?;
+    }
+    writeln!(w, "║")
+}
+
+fn rule_row(w: &mut impl fmt::Write, cells: &[(usize, String)]) -> fmt::Result {
+    for (i, (bits, _)) in cells.iter().enumerate() {
+        let joint = if i == 0 { '╟' } else { '╫' };
+        write!(w, "{}{}", joint, "─".repeat(2 * bits - 1))?;
+    }
+    writeln!(w, "╢")
+}
+
+/// Draws the storage as a box diagram: the bytes, the current bits and below
+/// them the name and value of each field, the value being the field's bits
+/// read as an unsigned integer. Bits are drawn in the order of their offsets,
+/// so fields are always contiguous and every group of 8 bits is one byte,
+/// labeled with its index in memory.
+///
+/// ```text
+/// ║    byte 0     ║    byte 1     ║
+/// ╟───────────────╫───────────────╢
+/// ║0 1 0 0 0 1 0 1 0 0 0 0 0 0 1 0║
+/// ╟───────╫───────╫───────────╫───╢
+/// ║version║  ihl  ║   dscp    ║ecn║
+/// ║   4   ║   5   ║     0     ║ 2 ║
+/// ```
+pub fn fmt_layout(
+    w: &mut impl fmt::Write,
+    data: &[u8],
+    fields: &[LayoutField],
+    order: BitOrder,
+    endian: Endian,
+) -> fmt::Result {
+    let len = data.len();
+    if len == 0 {
+        return Ok(());
+    }
+    let bytes: Vec<_> = (0..len)
+        .map(|group| {
+            let significance = match order {
+                BitOrder::Msb0 => len - 1 - group,
+                BitOrder::Lsb0 => group,
+            };
+            (8, format!("byte {}", byte_index(len, significance, endian)))
+        })
+        .collect();
+    text_row(w, &bytes)?;
+    rule_row(w, &bytes)?;
+
+    write!(w, "║")?;
+    for offset in 0..len * 8 {
+        let sep = if offset == 0 { "" } else { " " };
+        write!(w, "{}{}", sep, read_bits(data, offset, 1, order, endian))?;
+    }
+    writeln!(w, "║")?;
+
+    // fields in offset order, with the bits past the last one as a blank box
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    let mut fields: Vec<_> = fields.iter().filter(|field| field.width > 0).collect();
+    fields.sort_by_key(|field| field.offset);
+    let mut end = 0;
+    for field in fields {
+        let name = match field.index {
+            Some(index) => format!("{}[{}]", field.name, index),
+            None => field.name.to_string(),
+        };
+        names.push((field.width, name));
+        let value = if field.readable {
+            read_bits(data, field.offset, field.width, order, endian).to_string()
+        } else {
+            String::new()
+        };
+        values.push((field.width, value));
+        end = field.offset + field.width;
+    }
+    if end < len * 8 {
+        names.push((len * 8 - end, String::new()));
+        values.push((len * 8 - end, String::new()));
+    }
+    rule_row(w, &names)?;
+    text_row(w, &names)?;
+    text_row(w, &values)
+}
+
+macro_rules! int_codec {
+    ($($codec:ident: $container:ty => $target:ty;)*) => {
+        $(
+            #[doc = concat!("Codec of the `", stringify!($target), "` specifier.")]
+            pub enum $codec {}
+
+            impl $codec {
+                pub const fn from_bit_repr(repr: $container) -> $target {
+                    repr as $target
+                }
+
+                pub const fn try_from_bit_repr(
+                    repr: $container,
+                ) -> Result<$target, InvalidBitPattern<$container>> {
+                    Ok(repr as $target)
+                }
+
+                pub const fn from_target(target: $target) -> $container {
+                    target as $container
+                }
+            }
+
+            codec_trait! {
+                $codec: $container => $target;
+            }
+        )*
+    };
+}
+
+int_codec! {
+    U8Codec: u8 => u8;
+    U16Codec: u16 => u16;
+    U32Codec: u32 => u32;
+    U64Codec: u64 => u64;
+    U128Codec: u128 => u128;
+    I8Codec: u8 => i8;
+    I16Codec: u16 => i16;
+    I32Codec: u32 => i32;
+    I64Codec: u64 => i64;
+    I128Codec: u128 => i128;
+}
+
+/// Codec of the `char` specifier. Bit patterns that are not a unicode scalar
+/// value are invalid.
+pub enum CharCodec {}
+
+impl CharCodec {
+    pub const fn from_bit_repr(repr: u32) -> char {
+        match char::from_u32(repr) {
+            Some(c) => c,
+            None => panic!("invalid internal repr for `char`"),
+        }
+    }
+
+    pub const fn try_from_bit_repr(repr: u32) -> Result<char, InvalidBitPattern<u32>> {
+        match char::from_u32(repr) {
+            Some(c) => Ok(c),
+            None => Err(InvalidBitPattern { raw: repr }),
+        }
+    }
+
+    pub const fn from_target(target: char) -> u32 {
+        target as u32
+    }
+}
+
+codec_trait! {
+    CharCodec: u32 => char;
+}
+
+macro_rules! non_zero_codec {
+    ($($codec:ident: $container:ty => $ty:ident($int:ty);)*) => {
+        $(
+            #[doc = concat!("Codec of the `Option<", stringify!($ty), ">` specifier.")]
+            pub enum $codec {}
+
+            impl $codec {
+                pub const fn from_bit_repr(repr: $container) -> Option<core::num::$ty> {
+                    core::num::$ty::new(repr as $int)
+                }
+
+                pub const fn try_from_bit_repr(
+                    repr: $container,
+                ) -> Result<Option<core::num::$ty>, InvalidBitPattern<$container>> {
+                    Ok(Self::from_bit_repr(repr))
+                }
+
+                pub const fn from_target(target: Option<core::num::$ty>) -> $container {
+                    match target {
+                        Some(value) => value.get() as $container,
+                        None => 0,
+                    }
+                }
+            }
+
+            codec_trait! {
+                $codec: $container => Option<core::num::$ty>;
+            }
+        )*
+    };
+}
+
+non_zero_codec! {
+    NonZeroU8Codec: u8 => NonZeroU8(u8);
+    NonZeroU16Codec: u16 => NonZeroU16(u16);
+    NonZeroU32Codec: u32 => NonZeroU32(u32);
+    NonZeroU64Codec: u64 => NonZeroU64(u64);
+    NonZeroU128Codec: u128 => NonZeroU128(u128);
+    NonZeroI8Codec: u8 => NonZeroI8(i8);
+    NonZeroI16Codec: u16 => NonZeroI16(i16);
+    NonZeroI32Codec: u32 => NonZeroI32(i32);
+    NonZeroI64Codec: u64 => NonZeroI64(i64);
+    NonZeroI128Codec: u128 => NonZeroI128(i128);
+}
+
+/// Type-level bit width, used to pick the smallest unsigned integer holding a
+/// number of bits that is only known as a constant expression, e.g.
+/// `<Width<{ 0 + <B3 as Specifier>::BITS }> as Fit>::Uint`.
+pub struct Width<const N: usize>;
+
+pub trait Fit {
+    type Uint;
+}
+
+macro_rules! fit {
+    ($uint:ty => $($width:literal)*) => {
+        $(
+            impl Fit for Width<$width> {
+                type Uint = $uint;
+            }
+        )*
+    };
+}
+
+fit!(u8 => 0 1 2 3 4 5 6 7 8);
+fit!(u16 => 9 10 11 12 13 14 15 16);
+fit!(u32 => 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+fit!(u64 => 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48
+            49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64);
+fit!(u128 => 65 66 67 68 69 70 71 72 73 74 75 76 77 78 79 80
+             81 82 83 84 85 86 87 88 89 90 91 92 93 94 95 96
+             97 98 99 100 101 102 103 104 105 106 107 108 109 110 111 112
+             113 114 115 116 117 118 119 120 121 122 123 124 125 126 127 128);
@@ -0,0 +1,55 @@
+use core::ptr::NonNull;
+
+use crate::private::ReprBits;
+
+/// Handle to a bitfield living in memory that is accessed with volatile loads
+/// and stores only, such as a memory-mapped peripheral register.
+///
+/// Only bitfields declared with `#[bitfield(repr = u32)]` and the like can be
+/// accessed this way: every `read` is a single volatile load of the backing
+/// integer and every `write` a single volatile store. The handle holds a raw
+/// pointer, so no Rust reference to the device memory is ever created.
+///
+/// There is deliberately no `Debug` impl, as reading a register may have side
+/// effects.
+///
+/// ```ignore
+/// let control = unsafe { Volatile::<Control>::from_ptr(0x4000_1000 as *mut Control) };
+/// control.modify(|r| r.with_enable(true).with_mode(3));
+/// ```
+pub struct Volatile<T: ReprBits> {
+    ptr: NonNull<T>,
+}
+
+impl<T: ReprBits> Volatile<T> {
+    /// Accesses the memory at `ptr` as a volatile bitfield.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null, aligned for `T` and valid for volatile reads and
+    /// writes for as long as the returned handle is used.
+    pub const unsafe fn from_ptr(ptr: *mut T) -> Self {
+        Volatile {
+            ptr: NonNull::new_unchecked(ptr),
+        }
+    }
+
+    pub const fn as_ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    pub fn read(&self) -> T {
+        let bits = unsafe { self.ptr.cast::<T::Bits>().read_volatile() };
+        T::from_bits(bits)
+    }
+
+    pub fn write(&self, value: T) {
+        unsafe { self.ptr.cast::<T::Bits>().write_volatile(value.into_bits()) }
+    }
+
+    /// Reads the bitfield, passes it through `f` and writes back the result,
+    /// e.g. `reg.modify(|r| r.with_enable(true))`.
+    pub fn modify(&self, f: impl FnOnce(T) -> T) {
+        self.write(f(self.read()));
+    }
+}
@@ -0,0 +1,133 @@
+// The getters and setters read and write whole words rather than walking the
+// field one bit at a time. This test pins the layout down against a straight
+// bit-by-bit reference implementation, the way the accessors used to be
+// generated, for every width from B1 to B64 and a selection up to B128, at
+// every offset within a byte. Fields wider than a native word are read and
+// written in two halves.
+//
+//     ║  pre  ║        field        ║  post  ║     fill     ║
+//
+// `pre` shifts the field to each of the eight possible bit offsets, `post`
+// realigns to a byte boundary and `fill` pads the field's own width out to a
+// multiple of 8. The storage is filled with a noisy pattern first so that a
+// setter clobbering its neighbours shows up as a mismatch.
+//
+// Every field is checked twice: once at the edge of the storage as above,
+// where the accessors fall back to a byte loop, and once with 64 bits of
+// filler on either side, where they load and store a whole word.
+//
+//     ║  head  ║  pre  ║        field        ║  post  ║  tail  ║     fill     ║
+
+use bitfield::*;
+
+// Reference accessors: bit 0 is the most significant bit of the first byte.
+fn fetch_bit(data: &[u8], bit_index: usize) -> u128 {
+    ((data[bit_index / 8] >> (7 - bit_index % 8)) & 0x1) as u128
+}
+
+fn set_bit(data: &mut [u8], bit_index: usize, bit_val: u128) {
+    let offset = 7 - bit_index % 8;
+    data[bit_index / 8] &= !(1 << offset);
+    data[bit_index / 8] |= (bit_val as u8) << offset;
+}
+
+fn reference_get(data: &[u8], offset: usize, width: usize) -> u128 {
+    let mut val = 0u128;
+    for i in offset..offset + width {
+        val <<= 1;
+        val |= fetch_bit(data, i);
+    }
+    val
+}
+
+fn reference_set(data: &mut [u8], offset: usize, width: usize, mut val: u128) {
+    for i in (offset..offset + width).rev() {
+        set_bit(data, i, val & 0x1);
+        val >>= 1;
+    }
+}
+
+const PATTERNS: [u128; 7] = [
+    0,
+    u128::MAX,
+    0x5555_5555_5555_5555_5555_5555_5555_5555,
+    0xaaaa_aaaa_aaaa_aaaa_aaaa_aaaa_aaaa_aaaa,
+    0x0123_4567_89ab_cdef_fedc_ba98_7654_3210,
+    0xfedc_ba98_7654_3210_0123_4567_89ab_cdef,
+    0xffff_ffff_ffff_ffff_0000_0000_0000_0001,
+];
+
+fn noise(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i as u8).wrapping_mul(151).wrapping_add(89)).collect()
+}
+
+macro_rules! check_probe {
+    ($probe:ident, $offset:expr, $width:ty) => {{
+        let offset = $offset;
+        let width = <$width as Specifier>::BITS;
+        let mask = u128::MAX >> (128 - width);
+
+        let mut probe = $probe::new();
+        let len = probe.data.len();
+        probe.data.copy_from_slice(&noise(len));
+        assert_eq!(probe.get_field() as u128, reference_get(&probe.data, offset, width));
+
+        for pattern in PATTERNS {
+            let val = pattern & mask;
+            let mut expected = probe.data;
+            reference_set(&mut expected, offset, width, val);
+            probe.set_field(val as <$width as Specifier>::Target);
+            assert_eq!(probe.data, expected, "B{} at offset {}", width, offset);
+            assert_eq!(probe.get_field() as u128, val, "B{} at offset {}", width, offset);
+        }
+    }};
+}
+
+macro_rules! check_offsets {
+    ($width:ty, $fill:ty, $($pre:ident $post:ident),*) => {$({
+        #[bitfield]
+        struct Edge {
+            pre: $pre,
+            field: $width,
+            post: $post,
+            fill: $fill,
+        }
+
+        #[bitfield]
+        struct Inner {
+            head: B64,
+            pre: $pre,
+            field: $width,
+            post: $post,
+            tail: B64,
+            fill: $fill,
+        }
+
+        check_probe!(Edge, <$pre as Specifier>::BITS, $width);
+        check_probe!(Inner, 64 + <$pre as Specifier>::BITS, $width);
+    })*};
+}
+
+macro_rules! check_widths {
+    ($($width:ident $fill:ident),*) => {$(
+        check_offsets!(
+            $width, $fill,
+            B0 B8, B1 B7, B2 B6, B3 B5, B4 B4, B5 B3, B6 B2, B7 B1
+        );
+    )*};
+}
+
+fn main() {
+    check_widths!(
+        B1 B7, B2 B6, B3 B5, B4 B4, B5 B3, B6 B2, B7 B1, B8 B0,
+        B9 B7, B10 B6, B11 B5, B12 B4, B13 B3, B14 B2, B15 B1, B16 B0,
+        B17 B7, B18 B6, B19 B5, B20 B4, B21 B3, B22 B2, B23 B1, B24 B0,
+        B25 B7, B26 B6, B27 B5, B28 B4, B29 B3, B30 B2, B31 B1, B32 B0,
+        B33 B7, B34 B6, B35 B5, B36 B4, B37 B3, B38 B2, B39 B1, B40 B0,
+        B41 B7, B42 B6, B43 B5, B44 B4, B45 B3, B46 B2, B47 B1, B48 B0,
+        B49 B7, B50 B6, B51 B5, B52 B4, B53 B3, B54 B2, B55 B1, B56 B0,
+        B57 B7, B58 B6, B59 B5, B60 B4, B61 B3, B62 B2, B63 B1, B64 B0,
+        B65 B7, B66 B6, B71 B1, B72 B0, B80 B0, B99 B5,
+        B120 B0, B121 B7, B127 B1, B128 B0
+    );
+}
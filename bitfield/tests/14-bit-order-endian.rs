@@ -0,0 +1,104 @@
+// The layout of a bitfield can be tuned through two arguments on the attribute:
+//
+//     #[bitfield(bit_order = "msb0" | "lsb0", endian = "big" | "little")]
+//
+// Think of the storage as one unsigned integer made of all of its bytes. The
+// `endian` argument decides in which order those bytes sit in memory, and
+// `bit_order` decides whether the first field starts at the most significant
+// bit of that integer (msb0) or at the least significant one (lsb0). The
+// default of msb0 + big is the layout used by every other test case.
+//
+// For a = 0x1, b = 0x234, c = 0x5678 the four combinations come out as:
+//
+//     msb0 + big       12 34 56 78
+//     msb0 + little    78 56 34 12
+//     lsb0 + big       56 78 23 41
+//     lsb0 + little    41 23 78 56
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Implicit {
+    a: B4,
+    b: B12,
+    c: B16,
+}
+
+#[bitfield(bit_order = "msb0", endian = "big")]
+pub struct Msb0Big {
+    a: B4,
+    b: B12,
+    c: B16,
+}
+
+#[bitfield(bit_order = "msb0", endian = "little")]
+pub struct Msb0Little {
+    a: B4,
+    b: B12,
+    c: B16,
+}
+
+#[bitfield(bit_order = "lsb0", endian = "big")]
+pub struct Lsb0Big {
+    a: B4,
+    b: B12,
+    c: B16,
+}
+
+#[bitfield(endian = "little", bit_order = "lsb0")]
+pub struct Lsb0Little {
+    a: B4,
+    b: B12,
+    c: B16,
+}
+
+// Fields straddling byte boundaries at odd offsets, including one that spans
+// nine bytes.
+#[bitfield(bit_order = "lsb0", endian = "little")]
+pub struct Straddle {
+    a: B3,
+    b: B64,
+    c: B7,
+    d: B14,
+}
+
+macro_rules! check {
+    ($name:ident, $bytes:expr) => {{
+        let mut bitfield = $name::new();
+        bitfield.set_a(0x1);
+        bitfield.set_b(0x234);
+        bitfield.set_c(0x5678);
+        assert_eq!(bitfield.data, $bytes);
+        assert_eq!(bitfield.get_a(), 0x1);
+        assert_eq!(bitfield.get_b(), 0x234);
+        assert_eq!(bitfield.get_c(), 0x5678);
+
+        bitfield.set_b(0xfff);
+        assert_eq!(bitfield.get_a(), 0x1);
+        assert_eq!(bitfield.get_b(), 0xfff);
+        assert_eq!(bitfield.get_c(), 0x5678);
+    }};
+}
+
+fn main() {
+    check!(Implicit, [0x12, 0x34, 0x56, 0x78]);
+    check!(Msb0Big, [0x12, 0x34, 0x56, 0x78]);
+    check!(Msb0Little, [0x78, 0x56, 0x34, 0x12]);
+    check!(Lsb0Big, [0x56, 0x78, 0x23, 0x41]);
+    check!(Lsb0Little, [0x41, 0x23, 0x78, 0x56]);
+
+    let mut bitfield = Straddle::new();
+    bitfield.set_a(0b101);
+    bitfield.set_b(0xfedc_ba98_7654_3210);
+    bitfield.set_c(0x55);
+    bitfield.set_d(0x2aaa);
+    assert_eq!(bitfield.get_a(), 0b101);
+    assert_eq!(bitfield.get_b(), 0xfedc_ba98_7654_3210);
+    assert_eq!(bitfield.get_c(), 0x55);
+    assert_eq!(bitfield.get_d(), 0x2aaa);
+
+    // lsb0 + little places `a` in the low bits of the first byte and `b`
+    // directly above it.
+    assert_eq!(bitfield.data[0], 0b1000_0101);
+    assert_eq!(bitfield.data[1], 0x90);
+}
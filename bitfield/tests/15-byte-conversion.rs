@@ -0,0 +1,46 @@
+// A bitfield can be built from a received buffer and turned back into bytes
+// without reaching into its private storage:
+//
+//     pub fn from_bytes(bytes: [u8; N]) -> Self;
+//     pub fn into_bytes(self) -> [u8; N];
+//     pub fn as_bytes(&self) -> &[u8; N];
+//     pub fn try_from_slice(bytes: &[u8]) -> Result<Self, LengthMismatch>;
+//
+// along with the matching From<[u8; N]>, From<Self> for [u8; N] and
+// TryFrom<&[u8]> impls.
+
+use bitfield::*;
+use std::convert::TryFrom;
+
+#[bitfield]
+pub struct MyFourBytes {
+    a: B1,
+    b: B3,
+    c: B4,
+    d: B24,
+}
+
+fn main() {
+    let bitfield = MyFourBytes::from_bytes([0b1010_1100, 0x12, 0x34, 0x56]);
+    assert_eq!(bitfield.get_a(), 1);
+    assert_eq!(bitfield.get_b(), 0b010);
+    assert_eq!(bitfield.get_c(), 0b1100);
+    assert_eq!(bitfield.get_d(), 0x12_3456);
+    assert_eq!(bitfield.as_bytes(), &[0b1010_1100, 0x12, 0x34, 0x56]);
+    assert_eq!(bitfield.into_bytes(), [0b1010_1100, 0x12, 0x34, 0x56]);
+
+    let mut bitfield = MyFourBytes::from([0; 4]);
+    bitfield.set_d(0xab_cdef);
+    let bytes: [u8; 4] = bitfield.into();
+    assert_eq!(bytes, [0, 0xab, 0xcd, 0xef]);
+
+    let buffer = [0xff, 0x00, 0x00, 0x01, 0xee];
+    let bitfield = MyFourBytes::try_from_slice(&buffer[..4]).unwrap();
+    assert_eq!(bitfield.get_c(), 0xf);
+    assert_eq!(bitfield.get_d(), 1);
+
+    let err = MyFourBytes::try_from(&buffer[..]).err().unwrap();
+    assert_eq!(err, LengthMismatch { expected: 4, actual: 5 });
+    assert_eq!(err.to_string(), "expected a slice of 4 bytes, found 5 bytes");
+    assert!(MyFourBytes::try_from_slice(&buffer[..3]).is_err());
+}
@@ -0,0 +1,53 @@
+// Plain setters keep only the low bits of a value that is too wide for its
+// field, which silently spills nonsense into the stored value. Every field
+// also gets a checked setter that refuses such values:
+//
+//     pub fn set_b_checked(&mut self, val: u8) -> Result<(), OutOfBounds>;
+//
+// and the plain setter debug_asserts that the value fits, so a test build
+// panics at the offending call instead of carrying on with a wrapped value.
+
+use bitfield::*;
+use std::panic;
+
+#[bitfield]
+pub struct Header {
+    a: B2,
+    b: B6,
+    c: B8,
+    d: bool,
+    e: B7,
+    f: B64,
+}
+
+fn main() {
+    let mut header = Header::new();
+
+    assert_eq!(header.set_b_checked(63), Ok(()));
+    assert_eq!(header.get_b(), 63);
+    assert_eq!(header.set_b_checked(64), Err(OutOfBounds { bits: 6 }));
+    assert_eq!(header.get_b(), 63);
+    assert_eq!(header.get_a(), 0);
+    assert_eq!(header.get_c(), 0);
+
+    let err = header.set_a_checked(4).unwrap_err();
+    assert_eq!(err.bits, 2);
+    assert_eq!(err.to_string(), "value does not fit into 2 bits");
+
+    // Full-width fields accept every value of their type.
+    assert_eq!(header.set_c_checked(255), Ok(()));
+    assert_eq!(header.set_d_checked(true), Ok(()));
+    assert_eq!(header.set_f_checked(u64::MAX), Ok(()));
+    assert_eq!(header.get_f(), u64::MAX);
+
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(|| {
+        let mut header = Header::new();
+        header.set_e(128);
+    });
+    let _ = panic::take_hook();
+    // The setters are const fns, which cannot format the value into the
+    // message, so it names the field and its width only.
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "value does not fit into field `e` of 7 bits");
+}
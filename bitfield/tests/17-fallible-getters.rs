@@ -0,0 +1,92 @@
+// Not every bit pattern has to be meaningful for a specifier. Decoding goes
+// through the fallible try_from_bit_repr of the specifier's codec, and every
+// field gets a getter that surfaces it instead of panicking on untrusted input:
+//
+//     pub fn get_digit_or_err(&self) -> Result<Digit, InvalidBitPattern<u8>>;
+//
+// B1..B64 and bool accept every bit pattern, and derived enums reject the
+// patterns that do not belong to a variant.
+
+use bitfield::*;
+
+#[derive(Debug, PartialEq)]
+pub struct Digit(u8);
+
+// A binary coded decimal digit: 4 bits, of which only 0..=9 are valid.
+impl Specifier for Digit {
+    const BITS: usize = 4;
+    type Container = u8;
+    type Target = Digit;
+    type Codec = Digit;
+}
+
+// The conversions are const fns the accessors call, which codec! also wires up
+// as the Codec impl used by generic code.
+codec! {
+    impl Codec for Digit {
+        type Container = u8;
+        type Target = Digit;
+
+        const fn from_bit_repr(repr: u8) -> Digit {
+            match Self::try_from_bit_repr(repr) {
+                Ok(digit) => digit,
+                Err(_) => panic!("not a decimal digit"),
+            }
+        }
+
+        const fn try_from_bit_repr(repr: u8) -> Result<Digit, InvalidBitPattern<u8>> {
+            if repr < 10 {
+                Ok(Digit(repr))
+            } else {
+                Err(InvalidBitPattern { raw: repr })
+            }
+        }
+
+        const fn from_target(target: Digit) -> u8 {
+            target.0
+        }
+    }
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum TriggerMode {
+    Edge = 0,
+    Level = 1,
+}
+
+#[bitfield]
+pub struct Reading {
+    tens: Digit,
+    ones: Digit,
+    trigger_mode: TriggerMode,
+    valid: bool,
+    reserved: B6,
+}
+
+fn main() {
+    let mut reading = Reading::new();
+    reading.set_tens(Digit(4));
+    reading.set_ones(Digit(2));
+    reading.set_trigger_mode(TriggerMode::Level);
+    assert_eq!(reading.get_tens_or_err(), Ok(Digit(4)));
+    assert_eq!(reading.get_ones_or_err(), Ok(Digit(2)));
+    assert_eq!(reading.get_trigger_mode_or_err(), Ok(TriggerMode::Level));
+    assert_eq!(reading.get_valid_or_err(), Ok(false));
+    assert_eq!(reading.get_reserved_or_err(), Ok(0));
+
+    // Straight off the wire: 0xC is not a decimal digit.
+    let reading = Reading::from_bytes([0x7c, 0x80]);
+    assert_eq!(reading.get_tens_or_err(), Ok(Digit(7)));
+    let err = reading.get_ones_or_err().unwrap_err();
+    assert_eq!(err.raw, 0xc);
+    assert_eq!(err.to_string(), "invalid bit pattern 0b1100");
+    assert_eq!(reading.get_trigger_mode(), TriggerMode::Level);
+
+    assert_eq!(decode::<Digit>(0xc), Err(InvalidBitPattern { raw: 0xc }));
+    assert_eq!(decode::<TriggerMode>(1), Ok(TriggerMode::Level));
+    assert_eq!(decode::<B3>(5), Ok(5));
+}
+
+fn decode<S: Specifier>(repr: S::Container) -> Result<S::Target, InvalidBitPattern<S::Container>> {
+    <S::Codec as Codec>::try_from_bit_repr(repr)
+}
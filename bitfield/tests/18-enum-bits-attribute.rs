@@ -0,0 +1,73 @@
+// An enum whose number of variants is not a power of two can still be used as
+// a bitfield member by stating its width with #[bits = N]. Bit patterns that
+// do not belong to any variant are reported by the fallible getter.
+//
+//     #[derive(BitfieldSpecifier)]
+//     #[bits = 4]
+//     enum SmallPrime { ... }
+//
+// Every discriminant must still fit in N bits, which is checked at compile
+// time just like for power-of-two enums.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 2]
+pub enum Power {
+    Off,
+    Standby,
+    On,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 4]
+pub enum SmallPrime {
+    Two = 0b0010,
+    Three = 0b0011,
+    Five = 0b0101,
+    Seven = 0b0111,
+    Eleven = 0b1011,
+    Thirteen = 0b1101,
+}
+
+// Wider than strictly necessary is fine too.
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 2]
+pub enum TriggerMode {
+    Edge = 0,
+    Level = 1,
+}
+
+#[bitfield]
+pub struct MyBitfield {
+    #[bits = 2]
+    power: Power,
+    #[bits = 4]
+    small_prime: SmallPrime,
+    trigger_mode: TriggerMode,
+}
+
+fn main() {
+    assert_eq!(<Power as Specifier>::BITS, 2);
+    assert_eq!(<SmallPrime as Specifier>::BITS, 4);
+    assert_eq!(<TriggerMode as Specifier>::BITS, 2);
+
+    let mut bitfield = MyBitfield::new();
+    assert_eq!(bitfield.get_power(), Power::Off);
+    assert_eq!(bitfield.get_small_prime_or_err().unwrap_err().raw, 0);
+
+    bitfield.set_power(Power::On);
+    bitfield.set_small_prime(SmallPrime::Seven);
+    bitfield.set_trigger_mode(TriggerMode::Level);
+    assert_eq!(bitfield.get_power(), Power::On);
+    assert_eq!(bitfield.get_small_prime(), SmallPrime::Seven);
+    assert_eq!(bitfield.get_trigger_mode(), TriggerMode::Level);
+    assert_eq!(bitfield.as_bytes(), &[0b1001_1101]);
+
+    let bitfield = MyBitfield::from_bytes([0b1111_0111]);
+    assert_eq!(bitfield.get_power_or_err().unwrap_err().raw, 0b11);
+    assert_eq!(bitfield.get_small_prime_or_err(), Ok(SmallPrime::Thirteen));
+    assert_eq!(bitfield.get_trigger_mode_or_err().unwrap_err().raw, 0b11);
+    let p = bitfield.get_power_or_err().unwrap_or(Power::Standby);
+    assert_eq!(p, Power::Standby);
+}
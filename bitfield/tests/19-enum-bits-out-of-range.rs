@@ -0,0 +1,14 @@
+// With an explicit #[bits = N] the discriminants are still required to fit in
+// N bits.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier)]
+#[bits = 3]
+pub enum Opcode {
+    Load = 0b001,
+    Store = 0b010,
+    Jump = 0b1000,
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+// Besides the setters, every field gets a by-value `with_` method so that a
+// bitfield can be put together in a single expression:
+//
+//     let header = Header::new().with_version(4).with_ihl(5);
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Header {
+    version: B4,
+    ihl: B4,
+    dscp: B6,
+    ecn: B2,
+    total_length: B16,
+}
+
+fn main() {
+    let header = Header::new()
+        .with_version(4)
+        .with_ihl(5)
+        .with_total_length(0x54);
+    assert_eq!(header.get_version(), 4);
+    assert_eq!(header.get_ihl(), 5);
+    assert_eq!(header.get_dscp(), 0);
+    assert_eq!(header.get_ecn(), 0);
+    assert_eq!(header.get_total_length(), 0x54);
+    assert_eq!(header.into_bytes(), [0x45, 0x00, 0x00, 0x54]);
+
+    // Later calls overwrite earlier ones without disturbing other fields.
+    let header = Header::from_bytes([0x45, 0x00, 0x00, 0x54])
+        .with_ecn(0b11)
+        .with_ihl(6)
+        .with_ecn(0b01);
+    assert_eq!(header.into_bytes(), [0x46, 0x01, 0x00, 0x54]);
+}
@@ -0,0 +1,70 @@
+// Every generated constructor, getter, setter and conversion is a const fn, so
+// register values and lookup tables can be built at compile time.
+//
+// Trait methods cannot be called in const context, which is why the
+// conversions between a specifier's bit repr and its target type are provided
+// by inherent const fns on the type named by Specifier::Codec rather than by
+// methods of the Specifier trait.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum DeliveryMode {
+    Fixed = 0b00,
+    Lowest = 0b01,
+    Smi = 0b10,
+    Nmi = 0b11,
+}
+
+#[bitfield]
+pub struct Entry {
+    vector: B8,
+    delivery_mode: DeliveryMode,
+    masked: bool,
+    reserved: B5,
+}
+
+const ENTRY: Entry = Entry::new()
+    .with_vector(0x30)
+    .with_delivery_mode(DeliveryMode::Nmi)
+    .with_masked(true);
+
+const VECTOR: u8 = ENTRY.get_vector();
+const BYTES: [u8; 2] = Entry::new().with_vector(0xff).into_bytes();
+
+const fn table() -> [Entry; 4] {
+    let mut table = [Entry::new(), Entry::new(), Entry::new(), Entry::new()];
+    let mut i = 0;
+    while i < table.len() {
+        table[i].set_vector(0x20 + i as u8);
+        if table[i].set_reserved_checked(i as u8).is_err() {
+            panic!();
+        }
+        i += 1;
+    }
+    table
+}
+
+const TABLE: [Entry; 4] = table();
+
+const PARSED: Entry = match Entry::try_from_slice(&[0x41, 0b1010_0000]) {
+    Ok(entry) => entry,
+    Err(_) => panic!(),
+};
+
+const MODE: DeliveryMode = PARSED.get_delivery_mode();
+const MASKED: bool = PARSED.get_masked();
+
+fn main() {
+    assert_eq!(VECTOR, 0x30);
+    assert_eq!(ENTRY.get_delivery_mode(), DeliveryMode::Nmi);
+    assert_eq!(ENTRY.as_bytes(), &[0x30, 0b1110_0000]);
+    assert_eq!(BYTES, [0xff, 0]);
+
+    assert_eq!(TABLE[3].get_vector(), 0x23);
+    assert_eq!(TABLE[3].get_reserved(), 3);
+
+    assert_eq!(PARSED.get_vector(), 0x41);
+    assert_eq!(MODE, DeliveryMode::Smi);
+    assert!(MASKED);
+}
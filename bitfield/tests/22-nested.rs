@@ -0,0 +1,85 @@
+// A #[bitfield] struct is a Specifier itself, with BITS equal to its total
+// width, so smaller groups of bits can be composed into bigger layouts. The
+// getter and setter of a nested field hand out the nested bitfield by value.
+//
+//     ║ version ║          flags          ║             tl              ║ rest ║
+//     ║         ║ syn ack fin rst  window ║  kind          length       ║      ║
+
+use bitfield::*;
+
+#[bitfield]
+#[derive(Clone, Copy)]
+pub struct Flags {
+    syn: bool,
+    ack: bool,
+    fin: bool,
+    rst: bool,
+    window: B4,
+}
+
+#[bitfield]
+pub struct TypeLength {
+    kind: B4,
+    length: B12,
+}
+
+#[bitfield]
+pub struct Header {
+    version: B4,
+    flags: Flags,
+    tl: TypeLength,
+    rest: B4,
+}
+
+// Nesting goes arbitrarily deep, and the #[bits = N] check works the same.
+#[bitfield]
+pub struct Frame {
+    #[bits = 32]
+    header: Header,
+    #[bits = 8]
+    flags: Flags,
+}
+
+// The nested bitfield keeps its own bit order: `a` stays in the low bits of
+// the 16 bits taken up by `inner`.
+#[bitfield(bit_order = "lsb0", endian = "little")]
+pub struct Lsb0Inner {
+    a: B4,
+    b: B12,
+}
+
+#[bitfield]
+pub struct Outer {
+    inner: Lsb0Inner,
+}
+
+fn main() {
+    assert_eq!(<Flags as Specifier>::BITS, 8);
+    assert_eq!(<TypeLength as Specifier>::BITS, 16);
+    assert_eq!(<Header as Specifier>::BITS, 32);
+    assert_eq!(<Frame as Specifier>::BITS, 40);
+
+    let flags = Flags::new().with_syn(true).with_rst(true).with_window(0b0110);
+    let tl = TypeLength::new().with_kind(0xa).with_length(0x123);
+    let header = Header::new()
+        .with_version(4)
+        .with_flags(flags)
+        .with_tl(tl)
+        .with_rest(0xf);
+    assert_eq!(header.as_bytes(), &[0x49, 0x6a, 0x12, 0x3f]);
+
+    assert_eq!(header.get_flags().as_bytes(), flags.as_bytes());
+    assert!(header.get_flags().get_rst());
+    assert_eq!(header.get_tl().get_kind(), 0xa);
+    assert_eq!(header.get_tl().get_length(), 0x123);
+
+    let frame = Frame::new().with_header(header).with_flags(flags);
+    assert_eq!(frame.as_bytes(), &[0x49, 0x6a, 0x12, 0x3f, 0x96]);
+    assert_eq!(frame.get_header().get_tl().get_length(), 0x123);
+    assert_eq!(frame.get_flags().get_window(), 0b0110);
+
+    let outer = Outer::new().with_inner(Lsb0Inner::new().with_a(0x1).with_b(0x234));
+    assert_eq!(outer.as_bytes(), &[0x23, 0x41]);
+    assert_eq!(outer.get_inner().get_a(), 0x1);
+    assert_eq!(outer.get_inner().as_bytes(), &[0x41, 0x23]);
+}
@@ -0,0 +1,75 @@
+// Tuple structs are accepted as well, their accessors being named after the
+// position of each field:
+//
+//     pub fn get_0(&self) -> u8;
+//     pub fn set_0(&mut self, val: u8);
+//
+// A field can also be an array of specifiers, which lays the elements out one
+// after the other. Its accessors take the element index as first argument and
+// panic if it is out of bounds:
+//
+//     pub fn get_lanes(&self, index: usize) -> u8;
+//     pub fn set_lanes(&mut self, index: usize, val: u8);
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Pair(B4, B12);
+
+#[bitfield]
+pub struct Lanes {
+    mode: B4,
+    lanes: [B4; 8],
+    #[bits = 4]
+    status: [bool; 4],
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Level {
+    Off,
+    Low,
+    Mid,
+    High,
+}
+
+#[bitfield]
+pub struct Channels([Level; 3], B2);
+
+fn main() {
+    let mut pair = Pair::new().with_0(0xa);
+    pair.set_1(0x123);
+    assert_eq!(pair.get_0(), 0xa);
+    assert_eq!(pair.get_1(), 0x123);
+    assert_eq!(pair.as_bytes(), &[0xa1, 0x23]);
+    assert_eq!(pair.set_0_checked(0x10), Err(OutOfBounds { bits: 4 }));
+
+    assert_eq!(std::mem::size_of::<Lanes>(), 5);
+    let mut lanes = Lanes::new().with_mode(0xf);
+    for i in 0..8 {
+        lanes.set_lanes(i, i as u8 * 2);
+    }
+    lanes.set_status(1, true);
+    lanes = lanes.with_status(3, true);
+    assert_eq!(lanes.get_mode(), 0xf);
+    for i in 0..8 {
+        assert_eq!(lanes.get_lanes(i), i as u8 * 2);
+    }
+    assert_eq!(lanes.get_lanes_or_err(7), Ok(14));
+    assert!(!lanes.get_status(0));
+    assert!(lanes.get_status(1));
+    assert_eq!(lanes.as_bytes(), &[0xf0, 0x24, 0x68, 0xac, 0xe5]);
+    assert_eq!(lanes.set_lanes_checked(0, 16), Err(OutOfBounds { bits: 4 }));
+
+    let channels = Channels::new()
+        .with_0(0, Level::High)
+        .with_0(2, Level::Low)
+        .with_1(0b10);
+    assert_eq!(channels.get_0(0), Level::High);
+    assert_eq!(channels.get_0(1), Level::Off);
+    assert_eq!(channels.get_0(2), Level::Low);
+    assert_eq!(channels.as_bytes(), &[0b1100_0110]);
+
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(|| lanes.get_lanes(8));
+    assert!(result.is_err());
+}
@@ -0,0 +1,19 @@
+// Mistakes in the attributes on bitfield members are reported on the
+// offending tokens rather than as a panic of the whole macro.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct RedirectionTableEntry {
+    #[bit = 1]
+    acknowledged: bool,
+    trigger_mode: B7,
+}
+
+#[bitfield]
+pub struct Misnamed {
+    #[bits = "eight"]
+    a: B8,
+}
+
+fn main() {}
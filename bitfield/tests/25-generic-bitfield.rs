@@ -0,0 +1,12 @@
+// The storage layout of a #[bitfield] struct has to be known to the macro, so
+// generic parameters are rejected with an error pointing at them.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Generic<T: Specifier> {
+    a: T,
+    b: B8,
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+// Reserved bits take up space in the layout without getting any public API.
+// A field whose name starts with an underscore is pure padding; #[skip] does
+// the same for any other field, while #[skip(getters)] and #[skip(setters)]
+// only drop one side, e.g. for write-only command bits or read-only status
+// bits.
+//
+//     #[bitfield]
+//     pub struct Status {
+//         ready: bool,
+//         _reserved: B3,
+//         #[skip(setters)]
+//         error_code: B4,
+//     }
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Status {
+    ready: bool,
+    _r0: B3,
+    #[skip(setters)]
+    error_code: B4,
+    #[skip(getters)]
+    reset: bool,
+    #[skip]
+    reserved: B3,
+    _r1: B4,
+}
+
+fn main() {
+    let mut status = Status::from_bytes([0b1000_1010, 0b0111_0000]);
+    assert!(status.get_ready());
+    assert_eq!(status.get_error_code(), 0b1010);
+    assert_eq!(status.get_error_code_or_err(), Ok(0b1010));
+
+    // Setting the write-only bit leaves the padding alone.
+    status.set_reset(true);
+    status.set_ready(false);
+    assert_eq!(status.as_bytes(), &[0b0000_1010, 0b1111_0000]);
+
+    let status = Status::new().with_reset(true).with_ready(true);
+    assert_eq!(status.into_bytes(), [0b1000_0000, 0b1000_0000]);
+}
@@ -0,0 +1,21 @@
+// Skipped fields really have no accessors.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Status {
+    ready: bool,
+    #[skip(setters)]
+    error_code: B4,
+    #[skip(getters)]
+    reset: bool,
+    #[skip]
+    reserved: B2,
+}
+
+fn main() {
+    let mut status = Status::new();
+    status.set_error_code(1);
+    let _ = status.get_reset();
+    let _ = status.get_reserved();
+}
@@ -0,0 +1,12 @@
+// Unknown #[skip] arguments are reported on the argument.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Status {
+    ready: bool,
+    #[skip(checked_setters)]
+    error_code: B7,
+}
+
+fn main() {}
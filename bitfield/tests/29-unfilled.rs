@@ -0,0 +1,57 @@
+// With #[bitfield(filled = false)] the fields no longer have to add up to a
+// multiple of 8 bits. The storage is rounded up to the next whole byte and
+// the bits past the last field stay zero.
+//
+// The expected size can be stated with `bytes = N` and/or `bits = N`, which
+// is checked at compile time against the fields.
+
+use bitfield::*;
+
+#[bitfield(filled = false, bytes = 2, bits = 13)]
+pub struct Unfilled {
+    a: B5,
+    b: B8,
+}
+
+#[bitfield(filled = false, bit_order = "lsb0", endian = "little")]
+pub struct UnfilledLsb0 {
+    a: B5,
+    b: B8,
+}
+
+#[bitfield(bytes = 3)]
+pub struct Filled {
+    flag: bool,
+    inner: Unfilled,
+    rest: B10,
+}
+
+fn main() {
+    assert_eq!(std::mem::size_of::<Unfilled>(), 2);
+    assert_eq!(<Unfilled as Specifier>::BITS, 13);
+
+    let unfilled = Unfilled::new().with_a(0b10101).with_b(0xff);
+    assert_eq!(unfilled.as_bytes(), &[0b1010_1111, 0b1111_1000]);
+
+    // The trailing padding is cleared on the way in.
+    let unfilled = Unfilled::from_bytes([0xff, 0xff]);
+    assert_eq!(unfilled.get_a(), 0b11111);
+    assert_eq!(unfilled.get_b(), 0xff);
+    assert_eq!(unfilled.into_bytes(), [0xff, 0b1111_1000]);
+    let unfilled = Unfilled::try_from_slice(&[0xff, 0xff]).unwrap();
+    assert_eq!(unfilled.into_bytes(), [0xff, 0b1111_1000]);
+
+    let unfilled = UnfilledLsb0::new().with_a(0b10101).with_b(0xff);
+    assert_eq!(unfilled.as_bytes(), &[0b1111_0101, 0b0001_1111]);
+    let unfilled = UnfilledLsb0::from_bytes([0xff, 0xff]);
+    assert_eq!(unfilled.into_bytes(), [0xff, 0b0001_1111]);
+
+    // Only the 13 field bits are nested in other bitfields.
+    let filled = Filled::new()
+        .with_flag(true)
+        .with_inner(Unfilled::new().with_a(0b11111).with_b(1))
+        .with_rest(0b11_1111_1111);
+    assert_eq!(filled.as_bytes(), &[0b1111_1100, 0b0000_0111, 0b1111_1111]);
+    assert_eq!(filled.get_inner().get_a(), 0b11111);
+    assert_eq!(filled.get_inner().get_b(), 1);
+}
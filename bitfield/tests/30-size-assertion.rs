@@ -0,0 +1,18 @@
+// A layout that does not match the size stated in the attribute is reported
+// on the stated size.
+
+use bitfield::*;
+
+#[bitfield(bytes = 2)]
+pub struct TooSmall {
+    a: B4,
+    b: B4,
+}
+
+#[bitfield(filled = false, bits = 12)]
+pub struct TooLarge {
+    a: B4,
+    b: B9,
+}
+
+fn main() {}
@@ -0,0 +1,68 @@
+// #[derive(Debug)] on a bitfield shows the values of its fields rather than
+// the packed storage, while PartialEq, Eq and Hash compare the packed bytes.
+//
+//     #[bitfield]
+//     #[derive(Debug, PartialEq, Eq, Hash)]
+//     pub struct Header { ... }
+//
+//     Header { version: 4, ihl: 5, .. }
+//
+// Fields without getters are left out, and bits that do not correspond to any
+// value of the field's type are shown as an InvalidBitPattern.
+//
+// The derive has to come after #[bitfield]. Attributes above it are expanded
+// on the struct as written, so with the order swapped the derived Debug looks
+// for fields that #[bitfield] has already packed away:
+//
+//     #[derive(Debug)]
+//     #[bitfield]
+//     pub struct Header { ... }    // error: no field `version` on type `&Header`
+
+use bitfield::*;
+use std::collections::HashSet;
+
+#[derive(BitfieldSpecifier, Debug)]
+#[bits = 2]
+pub enum Ecn {
+    NotEct,
+    Ect1,
+    Ect0,
+}
+
+#[bitfield]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+    version: B4,
+    ihl: B4,
+    #[skip]
+    dscp: B6,
+    ecn: Ecn,
+}
+
+#[bitfield]
+#[derive(Debug)]
+pub struct Lanes([B4; 3], bool, B3);
+
+fn main() {
+    let header = Header::new().with_version(4).with_ihl(5).with_ecn(Ecn::Ect0);
+    assert_eq!(
+        format!("{:?}", header),
+        "Header { version: 4, ihl: 5, ecn: Ect0, .. }",
+    );
+
+    let invalid = Header::from_bytes([0x45, 0b0000_0011]);
+    assert_eq!(
+        format!("{:?}", invalid),
+        "Header { version: 4, ihl: 5, ecn: InvalidBitPattern { raw: 3 }, .. }",
+    );
+
+    // Equality and hashing look at every bit, including skipped fields.
+    let other = Header::from_bytes([0x45, 0b1000_0010]);
+    assert_eq!(header, Header::from_bytes([0x45, 0b0000_0010]));
+    assert_ne!(header, other);
+    let set: HashSet<Header> = [header, other, header].into_iter().collect();
+    assert_eq!(set.len(), 2);
+
+    let lanes = Lanes::new().with_0(1, 0xa).with_1(true).with_2(5);
+    assert_eq!(format!("{:?}", lanes), "Lanes([0, 10, 0], true, 5)");
+}
@@ -0,0 +1,77 @@
+// layout() draws the bitfield the same way as the diagram at the top of
+// 12-accessors-edge, with the current bits and the value of every field as an
+// unsigned integer:
+//
+//     ║    byte 0     ║    byte 1     ║    byte 2     ║    byte 3     ║
+//     ╟───────────────╫───────────────╫───────────────╫───────────────╢
+//     ║1 1 0 0 0 0 1 1 1 1 0 1 0 1 0 1 0 1 1 0 0 1 1 1 0 1 0 1 1 1 1 0║
+//     ╟─────────────────╫───────────╫─────────────────────────╫───────╢
+//     ║        a        ║     b     ║            c            ║   d   ║
+//     ║       391       ║    42     ║          5749           ║  14   ║
+//
+// fmt_layout() writes the same diagram to any fmt::Write.
+
+use bitfield::*;
+use std::fmt::Write;
+
+#[bitfield]
+pub struct EdgeCaseBytes {
+    a: B9,
+    b: B6,
+    c: B13,
+    d: B4,
+}
+
+#[derive(BitfieldSpecifier)]
+#[bits = 2]
+pub enum Mode {
+    Off,
+    On,
+    Auto,
+}
+
+#[bitfield(filled = false, bit_order = "lsb0", endian = "little")]
+pub struct Control {
+    lanes: [B2; 2],
+    mode: Mode,
+    #[skip(getters)]
+    reset: bool,
+    _r0: B2,
+    verbosity: B3,
+}
+
+fn main() {
+    let bitfield = EdgeCaseBytes::new()
+        .with_a(0b1100_0011_1)
+        .with_b(0b101_010)
+        .with_c(0x1675)
+        .with_d(0b1110);
+    let expected = "\
+║    byte 0     ║    byte 1     ║    byte 2     ║    byte 3     ║
+╟───────────────╫───────────────╫───────────────╫───────────────╢
+║1 1 0 0 0 0 1 1 1 1 0 1 0 1 0 1 0 1 1 0 0 1 1 1 0 1 0 1 1 1 1 0║
+╟─────────────────╫───────────╫─────────────────────────╫───────╢
+║        a        ║     b     ║            c            ║   d   ║
+║       391       ║    42     ║          5749           ║  14   ║
+";
+    assert_eq!(bitfield.layout(), expected);
+
+    let mut out = String::new();
+    writeln!(out, "edge:").unwrap();
+    bitfield.fmt_layout(&mut out).unwrap();
+    assert_eq!(out, format!("edge:\n{}", expected));
+
+    // Bits are drawn in offset order, so with lsb0 each byte reads from its
+    // least significant bit. Names and values that do not fit are cut short,
+    // and fields without getters or padding have no value.
+    let control = Control::from_bytes([0b1011_1001, 0b0101_1011]).with_lanes(1, 0b10);
+    let expected = "\
+║    byte 0     ║    byte 1     ║
+╟───────────────╫───────────────╢
+║1 0 0 1 1 1 0 1 1 1 0 1 0 0 0 0║
+╟───╫───╫───╫─╫───╫─────╫───────╢
+║lan║lan║mod║r║   ║verbo║       ║
+║ 1 ║ 2 ║ 3 ║ ║   ║  5  ║       ║
+";
+    assert_eq!(control.layout(), expected);
+}
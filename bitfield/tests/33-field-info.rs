@@ -0,0 +1,70 @@
+// Every #[bitfield] struct has a FIELDS table describing its layout, for
+// generating documentation, dissectors or C headers from the same definition.
+//
+//     for field in Header::FIELDS {
+//         println!("{} at bit {}, {} bits", field.name, field.offset, field.width);
+//     }
+//
+// Offsets count in the bit order of the bitfield. Padding and skipped fields
+// are listed too, since they take up bits all the same.
+
+use bitfield::*;
+use std::num::NonZeroU8;
+
+#[derive(BitfieldSpecifier)]
+pub enum Ecn {
+    NotEct,
+    Ect1,
+    Ect0,
+    Ce,
+}
+
+#[bitfield(bit_order = "lsb0")]
+pub struct Header {
+    version: B4,
+    ihl: B4,
+    #[skip]
+    dscp: B6,
+    ecn: Ecn,
+    _r0: B4,
+    lanes: [B3; 4],
+}
+
+#[bitfield]
+pub struct Pair(bool, B7);
+
+#[bitfield]
+pub struct Ids {
+    id: Option<core::num::NonZeroU16>,
+    tags: [Option<NonZeroU8>; 2],
+}
+
+const VERSION_WIDTH: usize = Header::FIELDS[0].width;
+
+fn main() {
+    assert_eq!(VERSION_WIDTH, 4);
+    assert_eq!(
+        Header::FIELDS,
+        &[
+            FieldInfo { name: "version", offset: 0, width: 4, ty: "B4", len: None },
+            FieldInfo { name: "ihl", offset: 4, width: 4, ty: "B4", len: None },
+            FieldInfo { name: "dscp", offset: 8, width: 6, ty: "B6", len: None },
+            FieldInfo { name: "ecn", offset: 14, width: 2, ty: "Ecn", len: None },
+            FieldInfo { name: "_r0", offset: 16, width: 4, ty: "B4", len: None },
+            FieldInfo { name: "lanes", offset: 20, width: 12, ty: "B3", len: Some(4) },
+        ],
+    );
+
+    assert_eq!(Pair::FIELDS.len(), 2);
+    assert_eq!(Pair::FIELDS[0].name, "0");
+    assert_eq!(Pair::FIELDS[0].ty, "bool");
+    assert_eq!(Pair::FIELDS[1].offset, 1);
+
+    // types are given as written, without the spacing of stringify!
+    assert_eq!(Ids::FIELDS[0].ty, "Option<core::num::NonZeroU16>");
+    assert_eq!(Ids::FIELDS[1].ty, "Option<NonZeroU8>");
+    assert_eq!(Ids::FIELDS[1].len, Some(2));
+
+    let total: usize = Header::FIELDS.iter().map(|field| field.width).sum();
+    assert_eq!(total, 32);
+}
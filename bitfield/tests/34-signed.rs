@@ -0,0 +1,94 @@
+// I1 through I64 store two's complement values. The getters sign extend into
+// the smallest signed primitive holding N bits, and the setters check against
+// the signed range -2^(N-1)..=2^(N-1)-1 instead of the unsigned one.
+//
+//     I1 ..= I8    i8
+//     I9 ..= I16   i16
+//     I17 ..= I32  i32
+//     I33 ..= I64  i64
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Reading {
+    flag: bool,
+    delta: I5,
+    temperature: I10,
+    offset: I40,
+    sign: I1,
+    _r0: B7,
+}
+
+// Every value of the signed range round trips and lands in the raw bits as
+// its two's complement; one past either end is rejected.
+macro_rules! exhaustive {
+    ($($spec:ident)*) => {$({
+        #[bitfield]
+        pub struct Probe {
+            _lead: B3,
+            value: $spec,
+            _tail: [B1; 8 - (3 + <$spec as Specifier>::BITS) % 8],
+        }
+
+        let bits = <$spec as Specifier>::BITS;
+        let min = -(1i64 << (bits - 1));
+        let max = (1i64 << (bits - 1)) - 1;
+        let mut probe = Probe::new();
+        for value in min..=max {
+            probe.set_value(value as _);
+            assert_eq!(probe.get_value() as i64, value);
+            let raw = (value as u64) & (u64::MAX >> (64 - bits));
+            let word = probe
+                .as_bytes()
+                .iter()
+                .fold(0u64, |word, byte| (word << 8) | *byte as u64);
+            let len = probe.as_bytes().len() * 8;
+            assert_eq!((word >> (len - 3 - bits)) & (u64::MAX >> (64 - bits)), raw);
+        }
+        if bits % 8 != 0 {
+            assert!(probe.set_value_checked((max + 1) as _).is_err());
+            assert!(probe.set_value_checked((min - 1) as _).is_err());
+        }
+    })*};
+}
+
+fn main() {
+    exhaustive!(I1 I2 I3 I4 I5 I6 I7 I8 I9 I10 I11 I12 I13 I14 I15 I16);
+
+    let mut reading = Reading::new()
+        .with_flag(true)
+        .with_delta(-16)
+        .with_temperature(-300)
+        .with_offset(-(1 << 39))
+        .with_sign(-1);
+    assert_eq!(reading.get_delta(), -16i8);
+    assert_eq!(reading.get_temperature(), -300i16);
+    assert_eq!(reading.get_offset(), -(1i64 << 39));
+    assert_eq!(reading.get_sign(), -1i8);
+
+    reading.set_delta(15);
+    reading.set_offset((1 << 39) - 1);
+    reading.set_sign(0);
+    assert_eq!(reading.get_delta(), 15);
+    assert_eq!(reading.get_offset(), (1 << 39) - 1);
+    assert_eq!(reading.get_sign(), 0);
+
+    // The raw bits are the low N bits of the two's complement.
+    let reading = Reading::new().with_delta(-1).with_temperature(-2);
+    assert_eq!(reading.as_bytes()[0], 0b0111_1111);
+    assert_eq!(reading.as_bytes()[1], 0b1111_1110);
+
+    let mut reading = reading;
+    assert_eq!(reading.set_delta_checked(16), Err(OutOfBounds { bits: 5 }),);
+    assert_eq!(reading.set_delta_checked(-17), Err(OutOfBounds { bits: 5 }),);
+    assert_eq!(
+        reading.set_temperature_checked(512),
+        Err(OutOfBounds { bits: 10 }),
+    );
+    assert_eq!(reading.set_temperature_checked(-512), Ok(()));
+    assert_eq!(reading.get_temperature(), -512);
+    assert_eq!(reading.get_delta(), -1);
+
+    const SIGN: i8 = Reading::new().with_sign(-1).get_sign();
+    assert_eq!(SIGN, -1);
+}
@@ -0,0 +1,68 @@
+// Integer primitives, `char` and `Option<NonZero*>` can be used as fields
+// directly. They take up all of their bits, so `u16` behaves like B16 except
+// that signed types come back signed.
+//
+// For `Option<NonZero*>` the all zero bit pattern is `None`, and for `char`
+// patterns that are not a unicode scalar value are invalid.
+
+use bitfield::*;
+use std::num::{NonZeroI16, NonZeroU8};
+
+#[bitfield]
+pub struct Packet {
+    kind: u8,
+    flags: B4,
+    delta: i16,
+    sequence: Option<NonZeroU8>,
+    _r0: B4,
+    length: u32,
+    checksum: u64,
+    ch: char,
+    bias: Option<NonZeroI16>,
+    level: i8,
+    big: i64,
+    small: i32,
+}
+
+fn main() {
+    assert_eq!(<u8 as Specifier>::BITS, 8);
+    assert_eq!(<i32 as Specifier>::BITS, 32);
+    assert_eq!(<char as Specifier>::BITS, 32);
+    assert_eq!(<Option<NonZeroU8> as Specifier>::BITS, 8);
+
+    let packet = Packet::new();
+    assert_eq!(packet.get_sequence(), None);
+    assert_eq!(packet.get_bias(), None);
+    assert_eq!(packet.get_ch(), '\0');
+
+    let packet = packet
+        .with_kind(0xa5)
+        .with_flags(0b1001)
+        .with_delta(-2)
+        .with_sequence(NonZeroU8::new(7))
+        .with_length(0xdead_beef)
+        .with_checksum(u64::MAX - 1)
+        .with_ch('é')
+        .with_bias(NonZeroI16::new(-300))
+        .with_level(i8::MIN)
+        .with_big(i64::MIN + 1)
+        .with_small(-1);
+    assert_eq!(packet.get_kind(), 0xa5u8);
+    assert_eq!(packet.get_flags(), 0b1001);
+    assert_eq!(packet.get_delta(), -2i16);
+    assert_eq!(packet.get_sequence(), NonZeroU8::new(7));
+    assert_eq!(packet.get_length(), 0xdead_beefu32);
+    assert_eq!(packet.get_checksum(), u64::MAX - 1);
+    assert_eq!(packet.get_ch(), 'é');
+    assert_eq!(packet.get_bias(), NonZeroI16::new(-300));
+    assert_eq!(packet.get_level(), i8::MIN);
+    assert_eq!(packet.get_big(), i64::MIN + 1);
+    assert_eq!(packet.get_small(), -1i32);
+    assert_eq!(&packet.as_bytes()[..4], &[0xa5, 0b1001_1111, 0xff, 0b1110_0000]);
+
+    // Surrogates are not chars.
+    let mut bytes = packet.into_bytes();
+    bytes[17..21].copy_from_slice(&0xd800u32.to_be_bytes());
+    let packet = Packet::from_bytes(bytes);
+    assert_eq!(packet.get_ch_or_err().unwrap_err().raw, 0xd800);
+}
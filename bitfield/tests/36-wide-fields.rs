@@ -0,0 +1,46 @@
+// B65 through B128 use u128 as container, as do the u128 and i128
+// primitives. Their accessors are checked bit by bit alongside the
+// narrower ones in 13-word-accessors.
+//
+// Bitfield structs of up to 128 bits can now be nested as well.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Nonce {
+    counter: B72,
+}
+
+#[bitfield(bit_order = "lsb0", endian = "little")]
+pub struct Header {
+    flags: B4,
+    nonce: Nonce,
+    delta: B100,
+    total: u128,
+    offset: i128,
+}
+
+fn main() {
+    assert_eq!(<B128 as Specifier>::BITS, 128);
+    assert_eq!(<B100 as Specifier>::BITS, 100);
+    assert_eq!(<Nonce as Specifier>::BITS, 72);
+
+    let nonce = Nonce::new().with_counter((1 << 71) | 0xabcd);
+    let header = Header::new()
+        .with_flags(0b1010)
+        .with_nonce(nonce)
+        .with_delta(1 << 99)
+        .with_total(u128::MAX - 7)
+        .with_offset(i128::MIN + 3);
+    assert_eq!(header.get_flags(), 0b1010);
+    assert_eq!(header.get_nonce().get_counter(), (1 << 71) | 0xabcd);
+    assert_eq!(header.get_delta(), 1 << 99);
+    assert_eq!(header.get_total(), u128::MAX - 7);
+    assert_eq!(header.get_offset(), i128::MIN + 3);
+
+    let mut header = header;
+    assert_eq!(header.set_delta_checked(1 << 100), Err(OutOfBounds { bits: 100 }));
+    assert_eq!(header.set_delta_checked((1 << 100) - 1), Ok(()));
+    assert_eq!(header.get_delta(), (1 << 100) - 1);
+    assert_eq!(header.get_total(), u128::MAX - 7);
+}
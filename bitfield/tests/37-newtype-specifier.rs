@@ -0,0 +1,87 @@
+// BitfieldSpecifier can also be derived for a struct with a single field whose
+// type is a specifier of itself, like the integer primitives, bool, derived
+// enums or other bitfields, so that domain types live directly in a bitfield.
+//
+//     #[derive(BitfieldSpecifier)]
+//     pub struct Port(#[bits = 12] u16);
+//
+// The getters return the newtype. With #[bits = N] only the low N bits of the
+// inner value are stored, and values that do not fit are caught by the range
+// check of the setters just like for a B12. A signed primitive cut down to N
+// bits holds an N bit two's complement value, like an I12.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq)]
+pub struct Port(#[bits = 12] u16);
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 5]
+pub struct Channel {
+    number: u8,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Mode {
+    Off,
+    On,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub struct Power(Mode);
+
+#[derive(BitfieldSpecifier, Debug, Clone, Copy, PartialEq)]
+pub struct Celsius(#[bits = 12] i16);
+
+#[bitfield]
+pub struct Reading {
+    temp: Celsius,
+    offset: Celsius,
+}
+
+#[bitfield]
+pub struct Endpoint {
+    port: Port,
+    channel: Channel,
+    power: Power,
+    flags: B6,
+}
+
+fn main() {
+    assert_eq!(<Port as Specifier>::BITS, 12);
+    assert_eq!(<Channel as Specifier>::BITS, 5);
+    assert_eq!(<Power as Specifier>::BITS, 1);
+
+    let mut endpoint = Endpoint::new()
+        .with_port(Port(0xabc))
+        .with_channel(Channel { number: 17 })
+        .with_power(Power(Mode::On));
+    assert_eq!(endpoint.get_port(), Port(0xabc));
+    assert_eq!(endpoint.get_channel(), Channel { number: 17 });
+    assert_eq!(endpoint.get_power(), Power(Mode::On));
+    assert_eq!(endpoint.as_bytes(), &[0xab, 0b1100_1000, 0b1100_0000]);
+
+    assert_eq!(
+        endpoint.set_port_checked(Port(0x1000)),
+        Err(OutOfBounds { bits: 12 }),
+    );
+    assert_eq!(
+        endpoint.set_channel_checked(Channel { number: 32 }),
+        Err(OutOfBounds { bits: 5 }),
+    );
+    assert_eq!(endpoint.get_port(), Port(0xabc));
+
+    let mut reading = Reading::new().with_temp(Celsius(-5)).with_offset(Celsius(2047));
+    assert_eq!(reading.get_temp(), Celsius(-5));
+    assert_eq!(reading.get_offset(), Celsius(2047));
+    assert_eq!(reading.as_bytes(), &[0xff, 0xb7, 0xff]);
+    assert_eq!(reading.set_temp_checked(Celsius(-2048)), Ok(()));
+    assert_eq!(reading.get_temp(), Celsius(-2048));
+    assert_eq!(reading.set_temp_checked(Celsius(2048)), Err(OutOfBounds { bits: 12 }));
+    assert_eq!(reading.set_temp_checked(Celsius(-2049)), Err(OutOfBounds { bits: 12 }));
+    assert_eq!(reading.get_temp(), Celsius(-2048));
+    assert_eq!(Reading::from_bytes([0x80, 0x07, 0xff]).get_offset(), Celsius(2047));
+
+    const PORT: Port = Endpoint::new().with_port(Port(80)).get_port();
+    assert_eq!(PORT, Port(80));
+}
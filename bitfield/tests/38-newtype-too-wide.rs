@@ -0,0 +1,11 @@
+// #[bits = N] on a newtype cannot be wider than the inner specifier.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier)]
+pub struct Port(#[bits = 12] u8);
+
+#[derive(BitfieldSpecifier)]
+pub struct Pair(u8, u8);
+
+fn main() {}
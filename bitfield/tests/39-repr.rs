@@ -0,0 +1,65 @@
+// #[bitfield(repr = u32)] stores the bitfield in a single u32 instead of a
+// byte array, so that a memory-mapped register can be accessed in one go. The
+// fields must add up to exactly the width of the integer.
+//
+// Offsets count from the most significant bit of the integer, or from the
+// least significant one with bit_order = "lsb0". There are no byte
+// conversions; the integer itself goes in and out through from_bits and
+// into_bits or the From impls.
+
+use bitfield::*;
+
+#[bitfield(repr = u32)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Control {
+    enable: bool,
+    mode: B3,
+    prescaler: B12,
+    reload: u16,
+}
+
+#[bitfield(repr = u16, bit_order = "lsb0")]
+pub struct Status {
+    ready: bool,
+    error: bool,
+    _r0: B6,
+    count: u8,
+}
+
+#[bitfield]
+pub struct Frame {
+    status: Status,
+    crc: u16,
+}
+
+fn main() {
+    assert_eq!(std::mem::size_of::<Control>(), 4);
+    assert_eq!(std::mem::align_of::<Control>(), std::mem::align_of::<u32>());
+
+    let control = Control::new()
+        .with_enable(true)
+        .with_mode(0b101)
+        .with_prescaler(0xabc)
+        .with_reload(0x1234);
+    assert_eq!(control.into_bits(), 0xdabc_1234);
+    assert_eq!(u32::from(control), 0xdabc_1234);
+    assert_eq!(Control::from(0xdabc_1234), control);
+    assert_eq!(control.get_prescaler(), 0xabc);
+
+    let status = Status::from_bits(0x2a03);
+    assert!(status.get_ready());
+    assert!(status.get_error());
+    assert_eq!(status.get_count(), 0x2a);
+    let status = status.with_error(false).with_count(0xff);
+    assert_eq!(status.into_bits(), 0xff01);
+
+    const STATUS: u16 = Status::new().with_count(7).into_bits();
+    assert_eq!(STATUS, 0x0700);
+
+    // Nested, the repr bitfield contributes its integer value.
+    let frame = Frame::new()
+        .with_status(Status::from_bits(0x8001))
+        .with_crc(0xbeef);
+    assert_eq!(frame.as_bytes(), &[0x80, 0x01, 0xbe, 0xef]);
+    assert_eq!(frame.get_status().into_bits(), 0x8001);
+}
@@ -0,0 +1,21 @@
+// With repr = N the fields have to cover the integer exactly.
+
+use bitfield::*;
+
+#[bitfield(repr = u32)]
+pub struct TooNarrow {
+    a: B12,
+    b: B16,
+}
+
+#[bitfield(repr = u16, endian = "little")]
+pub struct Endian {
+    a: B16,
+}
+
+#[bitfield(repr = i32)]
+pub struct Signed {
+    a: B32,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+// Volatile<T> is a handle to a bitfield that lives in device memory. It only
+// takes bitfields backed by an integer, so that reads and writes are single
+// volatile accesses of that integer, and modify() does a read-modify-write
+// with the with_ methods:
+//
+//     let control = unsafe { Volatile::<Control>::from_ptr(CONTROL_ADDR as *mut Control) };
+//     control.modify(|r| r.with_enable(true).with_mode(3));
+//
+// Here a plain buffer stands in for the device.
+
+use bitfield::*;
+
+#[bitfield(repr = u32)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Control {
+    enable: bool,
+    mode: B3,
+    prescaler: B12,
+    reload: u16,
+}
+
+fn main() {
+    let mut device = [0u32, 0x8000_0005, 0];
+    let control = unsafe { Volatile::<Control>::from_ptr(device.as_mut_ptr().add(1).cast()) };
+    assert_eq!(control.read().get_reload(), 5);
+    assert!(control.read().get_enable());
+
+    control.modify(|r| r.with_enable(false).with_mode(0b011));
+    assert_eq!(control.read(), Control::from_bits(0x3000_0005));
+    control.write(Control::new().with_prescaler(0xfff));
+    assert_eq!(control.as_ptr().cast::<u32>(), unsafe { device.as_mut_ptr().add(1) });
+    assert_eq!(device, [0, 0x0fff_0000, 0]);
+}
@@ -0,0 +1,67 @@
+// With #[bitfield(atomic)] the macro also generates an Atomic{Name} companion
+// backed by the matching AtomicU8..AtomicU64. Every field gets
+// load_<field>(Ordering), store_<field>(value, Ordering) and
+// fetch_update_<field>(set_order, fetch_order, f), and these update one field
+// without losing concurrent updates of the others.
+
+use bitfield::*;
+use std::sync::atomic::Ordering;
+
+#[bitfield(atomic)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Counters {
+    busy: bool,
+    hits: B15,
+    misses: u16,
+}
+
+#[bitfield(atomic, bit_order = "lsb0")]
+pub struct Flags {
+    ready: bool,
+    #[skip(setters)]
+    version: B3,
+    lanes: [B2; 2],
+}
+
+fn main() {
+    let counters = AtomicCounters::new(Counters::new().with_misses(7));
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    counters
+                        .fetch_update_hits(Ordering::AcqRel, Ordering::Acquire, |n| Some(n + 1))
+                        .unwrap();
+                    counters.store_busy(true, Ordering::Release);
+                }
+            });
+        }
+    });
+    assert_eq!(counters.load_hits(Ordering::Acquire), 4000);
+    assert_eq!(counters.load_misses(Ordering::Relaxed), 7);
+    assert!(counters.load_busy(Ordering::Relaxed));
+
+    // returning None leaves the field alone and reports the current value
+    let full = counters.fetch_update_misses(Ordering::SeqCst, Ordering::SeqCst, |n| {
+        if n < 7 { Some(n + 1) } else { None }
+    });
+    assert_eq!(full, Err(7));
+
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(|| counters.store_hits(1 << 15, Ordering::SeqCst));
+    let _ = std::panic::take_hook();
+    let message = *result.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "value does not fit into field `hits` of 15 bits");
+
+    counters.store(Counters::new().with_hits(1), Ordering::SeqCst);
+    assert_eq!(counters.load(Ordering::SeqCst), Counters::new().with_hits(1));
+    assert_eq!(counters.into_inner().into_bytes(), [0x00, 0x01, 0x00, 0x00]);
+
+    let flags = AtomicFlags::new(Flags::from_bytes([0b0000_0110]));
+    assert_eq!(flags.load_version(Ordering::Relaxed), 3);
+    flags.store_ready(true, Ordering::Relaxed);
+    flags.store_lanes(1, 2, Ordering::Relaxed);
+    assert_eq!(flags.load_lanes(0, Ordering::Relaxed), 0);
+    assert_eq!(flags.load_lanes(1, Ordering::Relaxed), 2);
+    assert_eq!(flags.load(Ordering::Relaxed).into_bytes(), [0b1000_0111]);
+}
@@ -0,0 +1,62 @@
+// With #[bitfield(views)] the macro also generates HeaderRef<'a> and
+// HeaderMut<'a>, which overlay the layout on borrowed bytes instead of
+// copying them into a Header. The slice must have exactly the size of the
+// bitfield; a view into a larger buffer is made by slicing it first.
+//
+// HeaderRef has the getters, HeaderMut has the getters and setters. The with_
+// methods, which take the bitfield by value, are only on Header itself.
+
+use bitfield::*;
+
+#[bitfield(views)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Header {
+    version: B4,
+    ihl: B4,
+    #[skip(setters)]
+    tos: u8,
+    length: u16,
+}
+
+#[bitfield(views, bit_order = "lsb0", endian = "little", filled = false)]
+pub struct Flags {
+    ready: bool,
+    lanes: [B2; 2],
+}
+
+fn main() {
+    let capture = [0x45, 0x10, 0x00, 0x54, 0xde, 0xad];
+    let header = HeaderRef::new(&capture[..4]).unwrap();
+    assert_eq!(header.get_version(), 4);
+    assert_eq!(header.get_ihl(), 5);
+    assert_eq!(header.get_tos(), 0x10);
+    assert_eq!(header.get_length(), 84);
+    assert_eq!(header.as_bytes(), &[0x45, 0x10, 0x00, 0x54]);
+    assert_eq!(header.to_bitfield(), Header::from_bytes([0x45, 0x10, 0x00, 0x54]));
+
+    let err = HeaderRef::new(&capture).err().unwrap();
+    assert_eq!(err, LengthMismatch { expected: 4, actual: 6 });
+
+    let mut buffer = capture;
+    assert!(HeaderMut::new(&mut buffer[3..]).is_err());
+    let mut header = HeaderMut::new(&mut buffer[..4]).unwrap();
+    header.set_ihl(6);
+    assert_eq!(header.set_version_checked(16), Err(OutOfBounds { bits: 4 }));
+    header.set_length(0x0102);
+    assert_eq!(header.as_view().get_length(), 0x0102);
+    assert_eq!(header.get_tos(), 0x10);
+    assert_eq!(buffer, [0x46, 0x10, 0x01, 0x02, 0xde, 0xad]);
+
+    let mut header = HeaderMut::new(&mut buffer[2..]).unwrap();
+    header.write(Header::new().with_length(7));
+    assert_eq!(buffer, [0x46, 0x10, 0x00, 0x00, 0x00, 0x07]);
+
+    // padding bits of the borrowed byte are left alone by the setters
+    let mut byte = [0b1000_0000];
+    let mut flags = FlagsMut::new(&mut byte).unwrap();
+    flags.set_ready(true);
+    flags.set_lanes(1, 3);
+    assert_eq!(flags.get_lanes(0), 0);
+    assert_eq!(byte, [0b1001_1001]);
+    assert_eq!(Flags::from_bytes(byte).into_bytes(), [0b0001_1001]);
+}
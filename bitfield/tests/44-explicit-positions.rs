@@ -0,0 +1,59 @@
+// Datasheets give fields as bit ranges and often list them out of order.
+// #[bits(lo..=hi)] pins a field to the offsets lo through hi, counted in the
+// bit order of the bitfield, so with bit_order = "lsb0" bit 0 is the least
+// significant bit as in most datasheets. The fields may then be declared in
+// any order. Once one field has a position all of them need one, and reserved
+// bits are spelled as padding fields.
+
+use bitfield::*;
+
+#[bitfield(bit_order = "lsb0", endian = "little")]
+#[derive(Debug)]
+pub struct Status {
+    #[bits(4..=7)]
+    channel: B4,
+    #[bits(0..=0)]
+    ready: bool,
+    #[bits(8..=15)]
+    count: u8,
+    #[bits(1..=3)]
+    _reserved: B3,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Mode {
+    Off = 0,
+    On = 1,
+    Auto = 2,
+    Test = 3,
+}
+
+#[bitfield]
+pub struct Control {
+    #[bits(6..=7)]
+    mode: Mode,
+    #[bits(0..=5)]
+    lanes: [B2; 3],
+}
+
+fn main() {
+    let status = Status::new().with_ready(true).with_channel(0xa).with_count(3);
+    assert_eq!(status.into_bytes(), [0xa1, 0x03]);
+
+    let offsets: Vec<_> = Status::FIELDS.iter().map(|f| (f.name, f.offset)).collect();
+    assert_eq!(
+        offsets,
+        [("channel", 4), ("ready", 0), ("count", 8), ("_reserved", 1)]
+    );
+    assert_eq!(
+        format!("{:?}", Status::from_bytes([0x51, 0x00])),
+        "Status { channel: 5, ready: true, count: 0, .. }"
+    );
+
+    let control = Control::new().with_mode(Mode::Auto).with_lanes(2, 1);
+    assert_eq!(control.into_bytes(), [0b0000_0110]);
+    let control = Control::from_bytes([0b1100_0010]);
+    assert_eq!(control.get_mode(), Mode::Auto);
+    assert_eq!(control.get_lanes(0), 3);
+    assert_eq!(control.get_lanes(1), 0);
+}
@@ -0,0 +1,38 @@
+// Explicit positions are checked at compile time: fields may not overlap,
+// every bit has to be covered by a field, reserved bits included, and the
+// range has to agree with the width of the field's specifier.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Overlap {
+    #[bits(0..=3)]
+    a: B4,
+    #[bits(3..=7)]
+    b: B5,
+}
+
+#[bitfield]
+pub struct Gap {
+    #[bits(0..=3)]
+    a: B4,
+    #[bits(6..=7)]
+    b: B2,
+}
+
+#[bitfield]
+pub struct Missing {
+    #[bits(0..=3)]
+    a: B4,
+    b: B4,
+}
+
+#[bitfield]
+pub struct Width {
+    #[bits(0..=2)]
+    a: B4,
+    #[bits(3..=7)]
+    b: B4,
+}
+
+fn main() {}
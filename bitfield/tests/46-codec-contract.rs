@@ -0,0 +1,81 @@
+// The codec of a hand-written Specifier impl has to implement the Codec trait
+// for the same Container and Target, which the compiler checks at the impl
+// rather than somewhere inside a #[bitfield] expansion.
+//
+// The accessors call the codec's inherent const fns though, which no trait can
+// require: a codec implementing only the trait is caught by the first
+// #[bitfield] using it. codec! writes both at once.
+
+use bitfield::*;
+
+pub struct Celsius(u8);
+
+// B8 converts between u8 and u8, not Celsius.
+impl Specifier for Celsius {
+    const BITS: usize = 8;
+    type Container = u8;
+    type Target = Celsius;
+    type Codec = B8;
+}
+
+pub struct Kelvin(u16);
+
+pub enum KelvinCodec {}
+
+// Only the inherent const fns, the Codec impl is missing.
+impl KelvinCodec {
+    pub const fn from_bit_repr(repr: u16) -> Kelvin {
+        Kelvin(repr)
+    }
+
+    pub const fn try_from_bit_repr(repr: u16) -> Result<Kelvin, InvalidBitPattern<u16>> {
+        Ok(Kelvin(repr))
+    }
+
+    pub const fn from_target(target: Kelvin) -> u16 {
+        target.0
+    }
+}
+
+impl Specifier for Kelvin {
+    const BITS: usize = 16;
+    type Container = u16;
+    type Target = Kelvin;
+    type Codec = KelvinCodec;
+}
+
+pub struct Rankine(u16);
+
+pub enum RankineCodec {}
+
+// Only the Codec impl, the inherent const fns are missing.
+impl Codec for RankineCodec {
+    type Container = u16;
+    type Target = Rankine;
+
+    fn from_bit_repr(repr: u16) -> Rankine {
+        Rankine(repr)
+    }
+
+    fn try_from_bit_repr(repr: u16) -> Result<Rankine, InvalidBitPattern<u16>> {
+        Ok(Rankine(repr))
+    }
+
+    fn from_target(target: Rankine) -> u16 {
+        target.0
+    }
+}
+
+impl Specifier for Rankine {
+    const BITS: usize = 16;
+    type Container = u16;
+    type Target = Rankine;
+    type Codec = RankineCodec;
+}
+
+#[bitfield]
+pub struct Thermometer {
+    reading: Rankine,
+}
+
+fn main() {}
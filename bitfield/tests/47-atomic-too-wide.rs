@@ -0,0 +1,12 @@
+// There is no stable atomic integer wider than 64 bits, so atomic bitfields
+// are limited to 64 bits, reported on the `atomic` argument.
+
+use bitfield::*;
+
+#[bitfield(atomic)]
+pub struct Descriptor {
+    address: B64,
+    flags: B8,
+}
+
+fn main() {}
@@ -0,0 +1,38 @@
+// Fields named after keywords are written as raw identifiers. The accessors,
+// the FIELDS table and the Debug output use the name without the `r#`:
+//
+//     #[bitfield]
+//     pub struct Descriptor {
+//         r#type: B4,
+//         ...
+//     }
+//
+//     descriptor.get_type();
+//     descriptor.set_type(0b1010);
+
+use bitfield::*;
+
+#[bitfield]
+#[derive(Debug)]
+pub struct Descriptor {
+    r#type: B4,
+    r#ref: bool,
+    r#priority: B3,
+}
+
+fn main() {
+    let mut descriptor = Descriptor::new().with_type(0b1010).with_ref(true);
+    descriptor.set_priority(5);
+    assert_eq!(descriptor.get_type(), 0b1010);
+    assert!(descriptor.get_ref());
+    assert_eq!(descriptor.get_priority(), 5);
+    assert_eq!(descriptor.set_type_checked(16), Err(OutOfBounds { bits: 4 }));
+
+    let names: Vec<_> = Descriptor::FIELDS.iter().map(|field| field.name).collect();
+    assert_eq!(names, ["type", "ref", "priority"]);
+    assert_eq!(
+        format!("{:?}", descriptor),
+        "Descriptor { type: 10, ref: true, priority: 5 }",
+    );
+    assert_eq!(descriptor.into_bytes(), [0b1010_1101]);
+}
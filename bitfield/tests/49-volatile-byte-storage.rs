@@ -0,0 +1,16 @@
+// Byte array storage could be split into several accesses, which a register
+// does not tolerate. Volatile only takes bitfields declared with a repr.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Status {
+    ready: bool,
+    count: B7,
+}
+
+fn main() {
+    let mut device = [0u8; 1];
+    let status = unsafe { Volatile::<Status>::from_ptr(device.as_mut_ptr().cast()) };
+    status.write(Status::new().with_ready(true));
+}
@@ -1,16 +1,53 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-specifier-types.rs");
-    //t.pass("tests/02-storage.rs");
-    //t.pass("tests/03-accessors.rs");
-    //t.compile_fail("tests/04-multiple-of-8bits.rs");
-    //t.pass("tests/05-accessor-signatures.rs");
-    //t.pass("tests/06-enums.rs");
-    //t.pass("tests/07-optional-discriminant.rs");
-    //t.compile_fail("tests/08-non-power-of-two.rs");
-    //t.compile_fail("tests/09-variant-out-of-range.rs");
-    //t.pass("tests/10-bits-attribute.rs");
-    //t.compile_fail("tests/11-bits-attribute-wrong.rs");
-    //t.pass("tests/12-accessors-edge.rs");
+    t.pass("tests/01-specifier-types.rs");
+    t.pass("tests/02-storage.rs");
+    t.pass("tests/03-accessors.rs");
+    t.compile_fail("tests/04-multiple-of-8bits.rs");
+    t.pass("tests/05-accessor-signatures.rs");
+    t.pass("tests/06-enums.rs");
+    t.pass("tests/07-optional-discriminant.rs");
+    t.compile_fail("tests/08-non-power-of-two.rs");
+    t.compile_fail("tests/09-variant-out-of-range.rs");
+    t.pass("tests/10-bits-attribute.rs");
+    t.compile_fail("tests/11-bits-attribute-wrong.rs");
+    t.pass("tests/12-accessors-edge.rs");
+    t.pass("tests/13-word-accessors.rs");
+    t.pass("tests/14-bit-order-endian.rs");
+    t.pass("tests/15-byte-conversion.rs");
+    t.pass("tests/16-checked-setters.rs");
+    t.pass("tests/17-fallible-getters.rs");
+    t.pass("tests/18-enum-bits-attribute.rs");
+    t.compile_fail("tests/19-enum-bits-out-of-range.rs");
+    t.pass("tests/20-with-methods.rs");
+    t.pass("tests/21-const-fn.rs");
+    t.pass("tests/22-nested.rs");
+    t.pass("tests/23-tuple-and-arrays.rs");
+    t.compile_fail("tests/24-unrecognized-field-attribute.rs");
+    t.compile_fail("tests/25-generic-bitfield.rs");
+    t.pass("tests/26-skip.rs");
+    t.compile_fail("tests/27-skip-no-accessors.rs");
+    t.compile_fail("tests/28-skip-wrong.rs");
+    t.pass("tests/29-unfilled.rs");
+    t.compile_fail("tests/30-size-assertion.rs");
+    t.pass("tests/31-derive-debug.rs");
+    t.pass("tests/32-layout.rs");
+    t.pass("tests/33-field-info.rs");
+    t.pass("tests/34-signed.rs");
+    t.pass("tests/35-primitives.rs");
+    t.pass("tests/36-wide-fields.rs");
+    t.pass("tests/37-newtype-specifier.rs");
+    t.compile_fail("tests/38-newtype-too-wide.rs");
+    t.pass("tests/39-repr.rs");
+    t.compile_fail("tests/40-repr-width.rs");
+    t.pass("tests/41-volatile.rs");
+    t.pass("tests/42-atomic.rs");
+    t.pass("tests/43-views.rs");
+    t.pass("tests/44-explicit-positions.rs");
+    t.compile_fail("tests/45-explicit-positions-wrong.rs");
+    t.compile_fail("tests/46-codec-contract.rs");
+    t.compile_fail("tests/47-atomic-too-wide.rs");
+    t.pass("tests/48-raw-identifiers.rs");
+    t.compile_fail("tests/49-volatile-byte-storage.rs");
 }